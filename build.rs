@@ -0,0 +1,13 @@
+use gl_generator::{Api, Fallbacks, GlobalGenerator, Profile, Registry};
+use std::env;
+use std::fs::File;
+use std::path::Path;
+
+// The gl crate only has core opengl, so the extensions we use that never became core get their own bindings
+fn main() {
+    let dest = env::var("OUT_DIR").expect("Getting OUT_DIR!");
+    let mut file = File::create(Path::new(&dest).join("gl_ext_bindings.rs")).expect("Creating extension bindings file!");
+    Registry::new(Api::Gl, (1, 0), Profile::Core, Fallbacks::None, ["GL_ARB_bindless_texture"])
+        .write_bindings(GlobalGenerator, &mut file)
+        .expect("Writing extension bindings!");
+}
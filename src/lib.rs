@@ -10,6 +10,26 @@ use std::ffi::CStr;
 pub mod render;
 pub mod util;
 
+// Bindings for the extensions that never became core ( so the gl crate doesn't have them ), generated by build.rs
+#[allow(clippy::all, dead_code, non_camel_case_types, non_snake_case, non_upper_case_globals, unused_imports)]
+pub(crate) mod gl_ext {
+    include!(concat!(env!("OUT_DIR"), "/gl_ext_bindings.rs"));
+}
+
+// Core since opengl 4.6 and the same value as the EXT_texture_filter_anisotropic enum, but the gl crate only goes up to 4.5
+pub(crate) const GL_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FE;
+pub(crate) const GL_MAX_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FF;
+
+// S3TC ( BC1-3 ) formats come from EXT_texture_compression_s3tc and EXT_texture_sRGB, they never became core so the gl crate doesn't have them
+pub const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: GLenum = 0x83F0;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: GLenum = 0x83F1;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT3_EXT: GLenum = 0x83F2;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT5_EXT: GLenum = 0x83F3;
+pub const GL_COMPRESSED_SRGB_S3TC_DXT1_EXT: GLenum = 0x8C4C;
+pub const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT: GLenum = 0x8C4D;
+pub const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT: GLenum = 0x8C4E;
+pub const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT: GLenum = 0x8C4F;
+
 #[macro_export]
 macro_rules! unwrap_result_or_ret {
     ($x:expr, $y:expr) => {
@@ -40,6 +60,59 @@ pub fn get_gl_version_str() -> String {
     }
 }
 
+/// Returns (major, minor)
+#[inline]
+pub fn get_gl_version() -> (GLint, GLint) {
+    let (mut major, mut minor): (GLint, GLint) = (0, 0);
+    unsafe {
+        gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+        gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+    }
+    (major, minor)
+}
+
+pub fn is_gl_extension_supported(name: &str) -> bool {
+    let mut n: GLint = 0;
+    unsafe {
+        gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut n);
+    }
+    let n: GLuint = unwrap_result_or_ret!(n.try_into(), false);
+    (0..n).any(|i| unsafe {
+        let ext = gl::GetStringi(gl::EXTENSIONS, i);
+        !ext.is_null() && CStr::from_ptr(ext as *const i8).to_bytes() == name.as_bytes()
+    })
+}
+
+/// Returns None if anisotropic filtering is not supported ( it's core since 4.6, before that it needs an extension )
+pub fn get_max_anisotropy() -> Option<f32> {
+    if get_gl_version() < (4, 6)
+        && !is_gl_extension_supported("GL_ARB_texture_filter_anisotropic")
+        && !is_gl_extension_supported("GL_EXT_texture_filter_anisotropic")
+    {
+        return None;
+    }
+    let mut max: GLfloat = 0.0;
+    unsafe {
+        gl::GetFloatv(GL_MAX_TEXTURE_MAX_ANISOTROPY, &mut max);
+    }
+    Some(max)
+}
+
+/// Checks a max anisotropy value against what the implementation supports, 1.0 ( no anisotropic filtering ) is always valid
+pub(crate) fn validate_max_anisotropy(max_anisotropy: f32) -> Result<(), String> {
+    if max_anisotropy.is_nan() || max_anisotropy < 1.0 {
+        return Err("Max anisotropy can't be less than 1.0!".to_owned());
+    }
+    if max_anisotropy == 1.0 {
+        return Ok(());
+    }
+    let max = unwrap_option_or_ret!(get_max_anisotropy(), Err("Anisotropic filtering is not supported!".to_owned()));
+    if max_anisotropy > max {
+        return Err(format!("Max anisotropy of {} is bigger than the maximum supported of {}!", max_anisotropy, max));
+    }
+    Ok(())
+}
+
 // NOTE: We use inline(always) here to make sure the optimiser gets the best chance to remove the bounds checks
 #[inline(always)]
 pub fn set_gl_clear_color(r: f32, g: f32, b: f32, a: f32) -> Result<(), &'static str> {
@@ -138,6 +211,88 @@ unsafe impl HasGLEnum for GLbyte {
     }
 }
 
+/// The bits of a 16 bit float, rust has no half float type so this is only useful for passing data that is already in that format to opengl
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Half(pub u16);
+
+impl Half {
+    /// Rounds to the nearest half float, values too big for one become infinity
+    pub fn from_f32(val: f32) -> Self {
+        let bits = val.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exp = ((bits >> 23) & 0xFF) as i32;
+        let mantissa = bits & 0x7F_FFFF;
+        if exp == 0xFF {
+            // NaNs keep a mantissa bit so they stay NaNs
+            return Half(sign | 0x7C00 | if mantissa != 0 { 0x200 } else { 0 });
+        }
+        // Rounds to nearest, ties to even, the shifted out bits are compared to half of the last bit kept
+        let round = |val: u32, shift: u32| -> u32 {
+            let kept = val >> shift;
+            let rest = val & ((1 << shift) - 1);
+            let halfway = 1 << (shift - 1);
+            kept + u32::from(rest > halfway || (rest == halfway && kept & 1 == 1))
+        };
+        let exp = exp - 127 + 15;
+        if exp >= 0x1F {
+            Half(sign | 0x7C00)
+        } else if exp <= 0 {
+            // Too small for a normal half float, so it becomes a subnormal one ( or zero )
+            if exp < -10 {
+                return Half(sign);
+            }
+            Half(sign | round(mantissa | 0x80_0000, (14 - exp) as u32) as u16)
+        } else {
+            // NOTE: Rounding up can carry into the exponent, which is still the right result ( up to infinity )
+            Half(sign | round(((exp as u32) << 23) | mantissa, 13) as u16)
+        }
+    }
+}
+
+unsafe impl HasGLEnum for Half {
+    #[inline(always)]
+    fn get_gl_type() -> GLenum {
+        gl::HALF_FLOAT
+    }
+}
+
+/// A whole pixel packed as 2 bits of alpha followed by 10 bits of blue, green and red ( from the most to the least significant bit ), for the RGB10_A2 internal format
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PackedRGB10A2(pub u32);
+
+unsafe impl HasGLEnum for PackedRGB10A2 {
+    #[inline(always)]
+    fn get_gl_type() -> GLenum {
+        gl::UNSIGNED_INT_2_10_10_10_REV
+    }
+}
+
+/// A whole pixel packed as unsigned 10 bit blue, 11 bit green and 11 bit red floats ( from the most to the least significant bit ), for the R11F_G11F_B10F internal format
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PackedR11FG11FB10F(pub u32);
+
+unsafe impl HasGLEnum for PackedR11FG11FB10F {
+    #[inline(always)]
+    fn get_gl_type() -> GLenum {
+        gl::UNSIGNED_INT_10F_11F_11F_REV
+    }
+}
+
+/// A whole pixel packed as 24 bits of depth followed by 8 bits of stencil ( from the most to the least significant bit ), for the DEPTH24_STENCIL8 internal format
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PackedDepth24Stencil8(pub u32);
+
+unsafe impl HasGLEnum for PackedDepth24Stencil8 {
+    #[inline(always)]
+    fn get_gl_type() -> GLenum {
+        gl::UNSIGNED_INT_24_8
+    }
+}
+
 #[inline]
 pub fn shader_glenum_to_string(e: GLenum) -> Option<&'static str> {
     match e {
@@ -151,45 +306,442 @@ pub fn shader_glenum_to_string(e: GLenum) -> Option<&'static str> {
 pub fn init(win: WindowedContext<NotCurrent>) -> Option<WindowedContext<PossiblyCurrent>> {
     let w = unwrap_result_or_ret!(unsafe { win.make_current() }, None);
     gl::load_with(|symbol| w.get_proc_address(symbol));
+    gl_ext::load_with(|symbol| w.get_proc_address(symbol));
     unsafe {
         gl::Enable(gl::BLEND);
         gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
 
+        // NOTE: Uploads expect tightly packed data by default, the ones that don't describe their layout with a render::texture::PixelStore
         gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
         gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
     }
     Some(w)
 }
 
-pub fn format_to_gl_internal_format(bpc: u8, format: GLenum) -> Option<(GLint, u8)> {
-    let cpp: u8 = match format { // Channels Per Pixel
-        gl::RED => 1,
-        gl::RG => 2,
-        gl::RGB => 3,
-        gl::RGBA => 4,
+/// Returns how many elements of type typ make up one pixel of data laid out as format, packed types hold a whole pixel in one element
+pub fn get_elements_per_pixel(typ: GLenum, format: GLenum) -> Option<u8> {
+    match typ {
+        gl::UNSIGNED_INT_2_10_10_10_REV => match format {
+            gl::RGBA | gl::BGRA | gl::RGBA_INTEGER | gl::BGRA_INTEGER => Some(1),
+            _ => None,
+        },
+        gl::UNSIGNED_INT_10F_11F_11F_REV => match format {
+            gl::RGB => Some(1),
+            _ => None,
+        },
+        gl::UNSIGNED_INT_24_8 => match format {
+            gl::DEPTH_STENCIL => Some(1),
+            _ => None,
+        },
+        _ => match format {
+            gl::DEPTH_COMPONENT => Some(1),
+            gl::RED | gl::RED_INTEGER => Some(1),
+            gl::RG | gl::RG_INTEGER => Some(2),
+            gl::RGB | gl::BGR | gl::RGB_INTEGER | gl::BGR_INTEGER => Some(3),
+            gl::RGBA | gl::BGRA | gl::RGBA_INTEGER | gl::BGRA_INTEGER => Some(4),
+            _ => None,
+        },
+    }
+}
+
+#[inline(always)]
+fn is_integer_format(format: GLenum) -> bool {
+    matches!(
+        format,
+        gl::RED_INTEGER
+            | gl::RG_INTEGER
+            | gl::RGB_INTEGER
+            | gl::BGR_INTEGER
+            | gl::RGBA_INTEGER
+            | gl::BGRA_INTEGER
+    )
+}
+
+/// Returns the sized internal format that data of type typ laid out as format is stored as by default and the number of elements per pixel
+/// NOTE: Formats like sRGB or 16 bit floats don't have a type of data that maps to them by default so they have to be asked for explicitly
+pub fn format_to_gl_internal_format(typ: GLenum, format: GLenum) -> Option<(GLint, u8)> {
+    let epp = get_elements_per_pixel(typ, format)?; // Elements Per Pixel
+
+    // Depth ( and stencil ) data has no channels so the type alone decides the internal format
+    if format == gl::DEPTH_COMPONENT || format == gl::DEPTH_STENCIL {
+        let internal_format = match typ {
+            gl::UNSIGNED_SHORT => gl::DEPTH_COMPONENT16,
+            gl::UNSIGNED_INT => gl::DEPTH_COMPONENT24,
+            gl::FLOAT => gl::DEPTH_COMPONENT32F,
+            gl::UNSIGNED_INT_24_8 => gl::DEPTH24_STENCIL8,
+            _ => return None,
+        };
+        return Some((unwrap_result_or_ret!(internal_format.try_into(), None), epp));
+    }
+
+    // Index of the internal format to use in the [R, RG, RGB, RGBA] arrays below
+    let channels = match format {
+        gl::RED | gl::RED_INTEGER => 0,
+        gl::RG | gl::RG_INTEGER => 1,
+        gl::RGB | gl::BGR | gl::RGB_INTEGER | gl::BGR_INTEGER => 2,
+        gl::RGBA | gl::BGRA | gl::RGBA_INTEGER | gl::BGRA_INTEGER => 3,
         _ => return None,
     };
 
-    let internal_format = match bpc { // Bits Per Channel
-        8 => match format {
-            gl::RED => gl::R8,
-            gl::RG => gl::RG8,
-            gl::RGB => gl::RGB8,
-            gl::RGBA => gl::RGBA8,
+    let internal_format = if is_integer_format(format) {
+        match typ {
+            gl::UNSIGNED_BYTE => [gl::R8UI, gl::RG8UI, gl::RGB8UI, gl::RGBA8UI][channels],
+            gl::BYTE => [gl::R8I, gl::RG8I, gl::RGB8I, gl::RGBA8I][channels],
+            gl::UNSIGNED_SHORT => [gl::R16UI, gl::RG16UI, gl::RGB16UI, gl::RGBA16UI][channels],
+            gl::SHORT => [gl::R16I, gl::RG16I, gl::RGB16I, gl::RGBA16I][channels],
+            gl::UNSIGNED_INT => [gl::R32UI, gl::RG32UI, gl::RGB32UI, gl::RGBA32UI][channels],
+            gl::INT => [gl::R32I, gl::RG32I, gl::RGB32I, gl::RGBA32I][channels],
+            gl::UNSIGNED_INT_2_10_10_10_REV => gl::RGB10_A2UI,
             _ => return None,
-        },
-
-        16 => match format {
-            gl::RED => gl::R16,
-            gl::RG => gl::RG16,
-            gl::RGB => gl::RGB16,
-            gl::RGBA => gl::RGBA16,
+        }
+    } else {
+        match typ {
+            gl::UNSIGNED_BYTE => [gl::R8, gl::RG8, gl::RGB8, gl::RGBA8][channels],
+            gl::BYTE => [gl::R8_SNORM, gl::RG8_SNORM, gl::RGB8_SNORM, gl::RGBA8_SNORM][channels],
+            gl::UNSIGNED_SHORT => [gl::R16, gl::RG16, gl::RGB16, gl::RGBA16][channels],
+            gl::SHORT => [gl::R16_SNORM, gl::RG16_SNORM, gl::RGB16_SNORM, gl::RGBA16_SNORM][channels],
+            gl::HALF_FLOAT => [gl::R16F, gl::RG16F, gl::RGB16F, gl::RGBA16F][channels],
+            gl::FLOAT => [gl::R32F, gl::RG32F, gl::RGB32F, gl::RGBA32F][channels],
+            gl::UNSIGNED_INT_2_10_10_10_REV => gl::RGB10_A2,
+            gl::UNSIGNED_INT_10F_11F_11F_REV => gl::R11F_G11F_B10F,
+            // NOTE: There are no normalized 32 bit integer formats
             _ => return None,
-        },
+        }
+    };
+    let internal_format: i32 = unwrap_result_or_ret!(internal_format.try_into(), None);
+
+    Some((internal_format, epp))
+}
+
+/// Returns true for internal formats that have a depth component ( including combined depth and stencil formats )
+#[inline]
+pub fn is_depth_internal_format(internal_format: GLenum) -> bool {
+    matches!(
+        internal_format,
+        gl::DEPTH_COMPONENT16 | gl::DEPTH_COMPONENT24 | gl::DEPTH_COMPONENT32F | gl::DEPTH24_STENCIL8
+    )
+}
+
+/// Returns the width and height in pixels of one block and the size in bytes of one block for block compressed internal formats, None for uncompressed ones
+pub fn get_compressed_block_info(internal_format: GLenum) -> Option<(usize, usize, usize)> {
+    match internal_format {
+        GL_COMPRESSED_RGB_S3TC_DXT1_EXT
+        | GL_COMPRESSED_RGBA_S3TC_DXT1_EXT
+        | GL_COMPRESSED_SRGB_S3TC_DXT1_EXT
+        | GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT
+        | gl::COMPRESSED_RED_RGTC1
+        | gl::COMPRESSED_SIGNED_RED_RGTC1
+        | gl::COMPRESSED_RGB8_ETC2
+        | gl::COMPRESSED_SRGB8_ETC2
+        | gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2
+        | gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2
+        | gl::COMPRESSED_R11_EAC
+        | gl::COMPRESSED_SIGNED_R11_EAC => Some((4, 4, 8)),
+        GL_COMPRESSED_RGBA_S3TC_DXT3_EXT
+        | GL_COMPRESSED_RGBA_S3TC_DXT5_EXT
+        | GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT
+        | GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT
+        | gl::COMPRESSED_RG_RGTC2
+        | gl::COMPRESSED_SIGNED_RG_RGTC2
+        | gl::COMPRESSED_RGBA_BPTC_UNORM
+        | gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM
+        | gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT
+        | gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT
+        | gl::COMPRESSED_RGBA8_ETC2_EAC
+        | gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC
+        | gl::COMPRESSED_RG11_EAC
+        | gl::COMPRESSED_SIGNED_RG11_EAC => Some((4, 4, 16)),
+        _ => None,
+    }
+}
+
+#[inline(always)]
+pub fn is_compressed_internal_format(internal_format: GLenum) -> bool {
+    get_compressed_block_info(internal_format).is_some()
+}
 
+/// Returns the size in bytes of an image of the given size in a compressed internal format, None if the format is not compressed or the size is too big
+/// The width and height are rounded up to whole blocks, any other dimension ( depth or layers ) just multiplies the size
+pub fn get_compressed_image_size(internal_format: GLenum, size: &[usize]) -> Option<usize> {
+    let (block_w, block_h, block_bytes) = get_compressed_block_info(internal_format)?;
+    let block_dims = [block_w, block_h];
+    size.iter().enumerate().try_fold(block_bytes, |acc, (i, s)| {
+        let blocks = match block_dims.get(i) {
+            Some(block_dim) => s.checked_add(block_dim - 1)? / block_dim,
+            None => *s,
+        };
+        acc.checked_mul(blocks)
+    })
+}
+
+/// Returns the size in bytes of one element of data of type typ
+#[inline]
+pub fn get_size_of_gl_type(typ: GLenum) -> Option<u8> {
+    match typ {
+        gl::UNSIGNED_BYTE | gl::BYTE => Some(1),
+        gl::UNSIGNED_SHORT | gl::SHORT | gl::HALF_FLOAT => Some(2),
+        gl::UNSIGNED_INT
+        | gl::INT
+        | gl::FLOAT
+        | gl::UNSIGNED_INT_2_10_10_10_REV
+        | gl::UNSIGNED_INT_10F_11F_11F_REV
+        | gl::UNSIGNED_INT_24_8 => Some(4),
+        gl::DOUBLE => Some(8),
+        _ => None,
+    }
+}
+
+/// Returns the size in bytes of one texel of an uncompressed sized internal format
+pub fn get_internal_format_texel_size(internal_format: GLenum) -> Option<usize> {
+    Some(match internal_format {
+        gl::R8 | gl::R8_SNORM | gl::R8UI | gl::R8I => 1,
+        gl::RG8 | gl::RG8_SNORM | gl::RG8UI | gl::RG8I => 2,
+        gl::R16 | gl::R16_SNORM | gl::R16F | gl::R16UI | gl::R16I | gl::DEPTH_COMPONENT16 => 2,
+        gl::RGB8 | gl::SRGB8 | gl::RGB8_SNORM | gl::RGB8UI | gl::RGB8I => 3,
+        gl::RGBA8 | gl::SRGB8_ALPHA8 | gl::RGBA8_SNORM | gl::RGBA8UI | gl::RGBA8I => 4,
+        gl::RG16 | gl::RG16_SNORM | gl::RG16F | gl::RG16UI | gl::RG16I => 4,
+        gl::R32F | gl::R32UI | gl::R32I => 4,
+        gl::RGB10_A2 | gl::RGB10_A2UI | gl::R11F_G11F_B10F => 4,
+        gl::DEPTH_COMPONENT24 | gl::DEPTH_COMPONENT32F | gl::DEPTH24_STENCIL8 => 4,
+        gl::RGB16 | gl::RGB16_SNORM | gl::RGB16F | gl::RGB16UI | gl::RGB16I => 6,
+        gl::RGBA16 | gl::RGBA16_SNORM | gl::RGBA16F | gl::RGBA16UI | gl::RGBA16I => 8,
+        gl::RG32F | gl::RG32UI | gl::RG32I => 8,
+        gl::RGB32F | gl::RGB32UI | gl::RGB32I => 12,
+        gl::RGBA32F | gl::RGBA32UI | gl::RGBA32I => 16,
         _ => return None,
+    })
+}
+
+/// Compressed formats that only differ in how their blocks are interpreted ( like their sRGB or signed variant )
+const COMPRESSED_CLASSES: [&[GLenum]; 8] = [
+    &[gl::COMPRESSED_RED_RGTC1, gl::COMPRESSED_SIGNED_RED_RGTC1],
+    &[gl::COMPRESSED_RG_RGTC2, gl::COMPRESSED_SIGNED_RG_RGTC2],
+    &[gl::COMPRESSED_RGBA_BPTC_UNORM, gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM],
+    &[gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT, gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT],
+    &[GL_COMPRESSED_RGB_S3TC_DXT1_EXT, GL_COMPRESSED_SRGB_S3TC_DXT1_EXT],
+    &[GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT],
+    &[GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT],
+    &[GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT],
+];
+
+#[inline]
+fn are_in_same_compressed_class(format: GLenum, other_format: GLenum) -> bool {
+    COMPRESSED_CLASSES
+        .iter()
+        .any(|class| class.contains(&format) && class.contains(&other_format))
+}
+
+/// Checks that glCopyImageSubData can copy between images of the two internal formats, which it does bit for bit without any conversion
+/// Uncompressed formats are compatible if their texels are the same size, and compressed formats are compatible with uncompressed formats whose texels are as big as their blocks
+/// NOTE: Depth and stencil formats are only compatible with themselves, and two different compressed formats only if they are in the same class ( like BPTC and its sRGB variant )
+pub fn are_internal_formats_copy_compatible(src_format: GLenum, dst_format: GLenum) -> bool {
+    if src_format == dst_format {
+        return true;
+    }
+    if is_depth_internal_format(src_format) || is_depth_internal_format(dst_format) {
+        return false;
+    }
+    let get_size = |internal_format: GLenum| match get_compressed_block_info(internal_format) {
+        Some((_, _, block_bytes)) => Some((block_bytes, true)),
+        None => get_internal_format_texel_size(internal_format).map(|size| (size, false)),
     };
-    let internal_format: i32 = unwrap_result_or_ret!(internal_format.try_into(), None);
+    match (get_size(src_format), get_size(dst_format)) {
+        (Some((_, true)), Some((_, true))) => are_in_same_compressed_class(src_format, dst_format),
+        (Some((src_size, _)), Some((dst_size, _))) => src_size == dst_size,
+        _ => false,
+    }
+}
+
+/// Checks that a texture view can reinterpret a texture of one internal format as the other, see glTextureView
+/// Uncompressed formats are in the same class if their texels are the same size, compressed formats only share a class with their other variants ( like their sRGB or signed one )
+/// NOTE: Depth and stencil formats are only compatible with themselves
+pub fn are_internal_formats_view_compatible(format: GLenum, view_format: GLenum) -> bool {
+    if format == view_format {
+        return true;
+    }
+    if is_depth_internal_format(format) || is_depth_internal_format(view_format) {
+        return false;
+    }
+    if is_compressed_internal_format(format) || is_compressed_internal_format(view_format) {
+        return are_in_same_compressed_class(format, view_format);
+    }
+    match (get_internal_format_texel_size(format), get_internal_format_texel_size(view_format)) {
+        (Some(size), Some(view_size)) => size == view_size,
+        _ => false,
+    }
+}
+
+/// Returns the type and format of the data that a texture with a sized internal format is naturally uploaded with
+pub fn gl_internal_format_to_format(internal_format: GLenum) -> Option<(GLenum, GLenum)> {
+    Some(match internal_format {
+        gl::R8 => (gl::UNSIGNED_BYTE, gl::RED),
+        gl::RG8 => (gl::UNSIGNED_BYTE, gl::RG),
+        gl::RGB8 | gl::SRGB8 => (gl::UNSIGNED_BYTE, gl::RGB),
+        gl::RGBA8 | gl::SRGB8_ALPHA8 => (gl::UNSIGNED_BYTE, gl::RGBA),
+        gl::R8_SNORM => (gl::BYTE, gl::RED),
+        gl::RG8_SNORM => (gl::BYTE, gl::RG),
+        gl::RGB8_SNORM => (gl::BYTE, gl::RGB),
+        gl::RGBA8_SNORM => (gl::BYTE, gl::RGBA),
+        gl::R16 => (gl::UNSIGNED_SHORT, gl::RED),
+        gl::RG16 => (gl::UNSIGNED_SHORT, gl::RG),
+        gl::RGB16 => (gl::UNSIGNED_SHORT, gl::RGB),
+        gl::RGBA16 => (gl::UNSIGNED_SHORT, gl::RGBA),
+        gl::R16_SNORM => (gl::SHORT, gl::RED),
+        gl::RG16_SNORM => (gl::SHORT, gl::RG),
+        gl::RGB16_SNORM => (gl::SHORT, gl::RGB),
+        gl::RGBA16_SNORM => (gl::SHORT, gl::RGBA),
+        // Half floats are naturally uploaded as f32s since rust has no half float type
+        gl::R16F | gl::R32F => (gl::FLOAT, gl::RED),
+        gl::RG16F | gl::RG32F => (gl::FLOAT, gl::RG),
+        gl::RGB16F | gl::RGB32F => (gl::FLOAT, gl::RGB),
+        gl::RGBA16F | gl::RGBA32F => (gl::FLOAT, gl::RGBA),
+        gl::R11F_G11F_B10F => (gl::UNSIGNED_INT_10F_11F_11F_REV, gl::RGB),
+        gl::RGB10_A2 => (gl::UNSIGNED_INT_2_10_10_10_REV, gl::RGBA),
+        gl::RGB10_A2UI => (gl::UNSIGNED_INT_2_10_10_10_REV, gl::RGBA_INTEGER),
+        gl::DEPTH_COMPONENT16 => (gl::UNSIGNED_SHORT, gl::DEPTH_COMPONENT),
+        gl::DEPTH_COMPONENT24 => (gl::UNSIGNED_INT, gl::DEPTH_COMPONENT),
+        gl::DEPTH_COMPONENT32F => (gl::FLOAT, gl::DEPTH_COMPONENT),
+        gl::DEPTH24_STENCIL8 => (gl::UNSIGNED_INT_24_8, gl::DEPTH_STENCIL),
+        gl::R8UI => (gl::UNSIGNED_BYTE, gl::RED_INTEGER),
+        gl::RG8UI => (gl::UNSIGNED_BYTE, gl::RG_INTEGER),
+        gl::RGB8UI => (gl::UNSIGNED_BYTE, gl::RGB_INTEGER),
+        gl::RGBA8UI => (gl::UNSIGNED_BYTE, gl::RGBA_INTEGER),
+        gl::R8I => (gl::BYTE, gl::RED_INTEGER),
+        gl::RG8I => (gl::BYTE, gl::RG_INTEGER),
+        gl::RGB8I => (gl::BYTE, gl::RGB_INTEGER),
+        gl::RGBA8I => (gl::BYTE, gl::RGBA_INTEGER),
+        gl::R16UI => (gl::UNSIGNED_SHORT, gl::RED_INTEGER),
+        gl::RG16UI => (gl::UNSIGNED_SHORT, gl::RG_INTEGER),
+        gl::RGB16UI => (gl::UNSIGNED_SHORT, gl::RGB_INTEGER),
+        gl::RGBA16UI => (gl::UNSIGNED_SHORT, gl::RGBA_INTEGER),
+        gl::R16I => (gl::SHORT, gl::RED_INTEGER),
+        gl::RG16I => (gl::SHORT, gl::RG_INTEGER),
+        gl::RGB16I => (gl::SHORT, gl::RGB_INTEGER),
+        gl::RGBA16I => (gl::SHORT, gl::RGBA_INTEGER),
+        gl::R32UI => (gl::UNSIGNED_INT, gl::RED_INTEGER),
+        gl::RG32UI => (gl::UNSIGNED_INT, gl::RG_INTEGER),
+        gl::RGB32UI => (gl::UNSIGNED_INT, gl::RGB_INTEGER),
+        gl::RGBA32UI => (gl::UNSIGNED_INT, gl::RGBA_INTEGER),
+        gl::R32I => (gl::INT, gl::RED_INTEGER),
+        gl::RG32I => (gl::INT, gl::RG_INTEGER),
+        gl::RGB32I => (gl::INT, gl::RGB_INTEGER),
+        gl::RGBA32I => (gl::INT, gl::RGBA_INTEGER),
+        _ => return None,
+    })
+}
+
+/// Checks that data of type typ laid out as format can be uploaded to a texture with a sized internal format
+/// Opengl converts between most types and channel counts, but integer textures can only be uploaded to with *_INTEGER formats and integer types and vice versa
+/// and depth ( and stencil ) textures can only be uploaded to with depth ( and stencil ) data and vice versa
+pub fn is_format_compatible_with_internal_format(internal_format: GLenum, typ: GLenum, format: GLenum) -> bool {
+    let (natural_typ, natural_format) = unwrap_option_or_ret!(gl_internal_format_to_format(internal_format), false);
+    if get_elements_per_pixel(typ, format).is_none() {
+        return false;
+    }
+    if natural_format == gl::DEPTH_STENCIL {
+        format == gl::DEPTH_STENCIL && typ == natural_typ
+    } else if natural_format == gl::DEPTH_COMPONENT || format == gl::DEPTH_COMPONENT || format == gl::DEPTH_STENCIL {
+        format == natural_format
+    } else if is_integer_format(natural_format) {
+        is_integer_format(format) && typ != gl::FLOAT && typ != gl::HALF_FLOAT
+    } else {
+        !is_integer_format(format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_image_size_rounds_up_to_blocks() {
+        // 5 * 3 pixels is 2 * 1 blocks
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[5, 3]), Some(32));
+        assert_eq!(get_compressed_image_size(GL_COMPRESSED_RGB_S3TC_DXT1_EXT, &[5, 3]), Some(16));
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[1, 1]), Some(16));
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[8, 8]), Some(64));
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[0, 4]), Some(0));
+    }
+
+    #[test]
+    fn compressed_image_size_depth_and_layers_are_not_blocks() {
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[4, 4, 3]), Some(48));
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RED_RGTC1, &[8, 4, 2]), Some(32));
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[5, 3, 6]), Some(192));
+    }
+
+    #[test]
+    fn compressed_image_size_of_unknown_formats_and_overflow() {
+        assert_eq!(get_compressed_image_size(gl::RGBA8, &[4, 4]), None);
+        assert_eq!(get_compressed_image_size(0, &[4, 4]), None);
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[usize::MAX, 4]), None);
+        assert_eq!(get_compressed_image_size(gl::COMPRESSED_RGBA_BPTC_UNORM, &[1 << 40, 1 << 40]), None);
+    }
+
+    #[test]
+    fn compressed_formats_copy_compatible_within_their_class() {
+        assert!(are_internal_formats_copy_compatible(gl::COMPRESSED_RGBA_BPTC_UNORM, gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM));
+        assert!(are_internal_formats_copy_compatible(gl::COMPRESSED_RED_RGTC1, gl::COMPRESSED_SIGNED_RED_RGTC1));
+        assert!(are_internal_formats_copy_compatible(gl::COMPRESSED_RGBA_BPTC_UNORM, gl::RGBA32UI));
+        assert!(are_internal_formats_copy_compatible(gl::RG32F, gl::COMPRESSED_RED_RGTC1));
+        // Same block size but a different class
+        assert!(!are_internal_formats_copy_compatible(gl::COMPRESSED_RGBA_BPTC_UNORM, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT));
+        assert!(!are_internal_formats_copy_compatible(gl::COMPRESSED_RGBA_BPTC_UNORM, gl::RGBA8));
+    }
+
+    #[test]
+    fn half_exact_values() {
+        assert_eq!(Half::from_f32(1.0), Half(0x3C00));
+        assert_eq!(Half::from_f32(-2.0), Half(0xC000));
+        assert_eq!(Half::from_f32(0.5), Half(0x3800));
+        assert_eq!(Half::from_f32(65504.0), Half(0x7BFF));
+        assert_eq!(Half::from_f32(0.0), Half(0x0000));
+        assert_eq!(Half::from_f32(-0.0), Half(0x8000));
+        // Smallest normal half float
+        assert_eq!(Half::from_f32(2f32.powi(-14)), Half(0x0400));
+    }
 
-    Some((internal_format, cpp))
+    #[test]
+    fn half_ties_to_even() {
+        assert_eq!(Half::from_f32(1.0 + 2f32.powi(-11)), Half(0x3C00));
+        assert_eq!(Half::from_f32(1.0 + 3.0 * 2f32.powi(-11)), Half(0x3C02));
+        assert_eq!(Half::from_f32(1.0 + 1.5 * 2f32.powi(-11)), Half(0x3C01));
+        // Rounding up carries into the exponent
+        assert_eq!(Half::from_f32(2.0 - 2f32.powi(-12)), Half(0x4000));
+        // Ties between subnormals
+        assert_eq!(Half::from_f32(1.5 * 2f32.powi(-24)), Half(0x0002));
+        assert_eq!(Half::from_f32(2.5 * 2f32.powi(-24)), Half(0x0002));
+    }
+
+    #[test]
+    fn half_overflow_to_infinity() {
+        assert_eq!(Half::from_f32(65519.0), Half(0x7BFF));
+        assert_eq!(Half::from_f32(65520.0), Half(0x7C00));
+        assert_eq!(Half::from_f32(-1e10), Half(0xFC00));
+        assert_eq!(Half::from_f32(f32::MAX), Half(0x7C00));
+        assert_eq!(Half::from_f32(f32::INFINITY), Half(0x7C00));
+        assert_eq!(Half::from_f32(f32::NEG_INFINITY), Half(0xFC00));
+    }
+
+    #[test]
+    fn half_subnormals_and_underflow() {
+        assert_eq!(Half::from_f32(2f32.powi(-24)), Half(0x0001));
+        assert_eq!(Half::from_f32(-2f32.powi(-24)), Half(0x8001));
+        assert_eq!(Half::from_f32(2f32.powi(-14) - 2f32.powi(-24)), Half(0x03FF));
+        // Exactly half of the smallest subnormal ties to even, so to zero, anything above rounds up
+        assert_eq!(Half::from_f32(2f32.powi(-25)), Half(0x0000));
+        assert_eq!(Half::from_f32(1.25 * 2f32.powi(-25)), Half(0x0001));
+        assert_eq!(Half::from_f32(2f32.powi(-26)), Half(0x0000));
+        assert_eq!(Half::from_f32(-2f32.powi(-26)), Half(0x8000));
+        assert_eq!(Half::from_f32(f32::MIN_POSITIVE), Half(0x0000));
+    }
+
+    #[test]
+    fn half_nan() {
+        for val in [f32::NAN, -f32::NAN, f32::from_bits(0x7F80_0001)] {
+            let Half(bits) = Half::from_f32(val);
+            assert_eq!(bits & 0x7C00, 0x7C00);
+            assert_ne!(bits & 0x3FF, 0);
+        }
+    }
 }
@@ -0,0 +1,109 @@
+use crate::gl_ext;
+use gl::types::*;
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Bindless textures need ARB_bindless_texture, which never became core
+#[inline]
+pub fn is_bindless_texture_supported() -> bool {
+    crate::is_gl_extension_supported("GL_ARB_bindless_texture") && gl_ext::GetTextureHandleARB::is_loaded()
+}
+
+/// A texture handle that is resident ( usable by shaders ) for as long as this lives
+/// The handle can be put in a uniform ( see Program::set_uniform_texture_handle ) or in a buffer as a u64 ( or a uvec2 )
+/// NOTE: Dropping the texture ( or the sampler ) the handle was made from makes it non-resident right away, using it after that is undefined behaviour on the gpu side
+pub struct ResidentTextureHandle {
+    handle: GLuint64,
+    /// Shared with the texture and sampler the handle was made from, whoever makes it non-resident first clears it
+    resident: Arc<AtomicBool>,
+}
+
+impl ResidentTextureHandle {
+    #[inline(always)]
+    pub fn get_handle(&self) -> GLuint64 {
+        self.handle
+    }
+
+    /// The handle split as a glsl uvec2 expects it, for buffers that can't hold 64 bit integers
+    #[inline(always)]
+    pub fn get_handle_as_uvec2(&self) -> [u32; 2] {
+        [self.handle as u32, (self.handle >> 32) as u32]
+    }
+
+    /// False once the texture or sampler the handle was made from has been dropped
+    #[inline(always)]
+    pub fn is_resident(&self) -> bool {
+        self.resident.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for ResidentTextureHandle {
+    fn drop(&mut self) {
+        make_non_resident(self.handle, &self.resident);
+    }
+}
+
+#[inline(always)]
+fn make_non_resident(handle: GLuint64, resident: &AtomicBool) {
+    if resident.swap(false, Ordering::SeqCst) {
+        unsafe {
+            gl_ext::MakeTextureHandleNonResidentARB(handle);
+        }
+    }
+}
+
+/// The handles made resident from a texture or sampler, so they can be made non-resident before it's deleted
+#[derive(Default)]
+pub(crate) struct ResidentHandles {
+    handles: RefCell<Vec<(GLuint64, Arc<AtomicBool>)>>,
+    /// Set once opengl has made a handle, from then on the state of the texture or sampler is immutable even after the handle is made non-resident
+    has_handle: Cell<bool>,
+}
+
+impl ResidentHandles {
+    /// Makes every handle that is still resident non-resident
+    pub(crate) fn release_all(&self) {
+        for (handle, resident) in self.handles.borrow_mut().drain(..) {
+            make_non_resident(handle, &resident);
+        }
+    }
+
+    /// Errors if a handle has been made, since opengl ignores every change to the parameters ( or storage ) after that with GL_INVALID_OPERATION
+    pub(crate) fn validate_no_handle(&self) -> Result<(), String> {
+        if self.has_handle.get() {
+            return Err("Parameters and storage can't be changed once a bindless handle has been made!".to_owned());
+        }
+        Ok(())
+    }
+
+    fn track(&self, handle: GLuint64, resident: &Arc<AtomicBool>) {
+        let mut handles = self.handles.borrow_mut();
+        handles.retain(|(_, resident)| resident.load(Ordering::SeqCst));
+        handles.push((handle, Arc::clone(resident)));
+    }
+}
+
+// NEEDED BY Texture
+/// Makes the handle resident and tracks it in everything it was made from
+pub(crate) fn make_resident(handle: GLuint64, owners: &[&ResidentHandles]) -> Result<ResidentTextureHandle, String> {
+    if handle == 0 {
+        return Err("Opengl could not create a handle for the texture, it has to be complete to get one!".to_owned());
+    }
+    // NOTE: Getting the handle is what makes the state immutable, so this is recorded even if it can't be made resident
+    for owner in owners {
+        owner.has_handle.set(true);
+    }
+    // NOTE: A handle can only be made resident once, and the same texture ( and sampler ) always gives the same handle
+    if unsafe { gl_ext::IsTextureHandleResidentARB(handle) } == gl::TRUE {
+        return Err("Handle is already resident, drop the other ResidentTextureHandle of the texture first!".to_owned());
+    }
+    unsafe {
+        gl_ext::MakeTextureHandleResidentARB(handle);
+    }
+    let resident = Arc::new(AtomicBool::new(true));
+    for owner in owners {
+        owner.track(handle, &resident);
+    }
+    Ok(ResidentTextureHandle { handle, resident })
+}
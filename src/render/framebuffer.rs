@@ -0,0 +1,235 @@
+use crate::render::renderbuffer::Renderbuffer;
+use crate::render::texture::{CubeFace, LayeredTexture, Texture, Texture2D, TextureCube};
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret};
+use gl::types::*;
+use std::convert::TryInto;
+use one_user::one_user;
+
+impl framebuffer_binder::OnBind for Framebuffer {
+    #[inline(always)]
+    fn on_bind<const BI: usize>(&self) {
+        self.bind_fb();
+    }
+}
+
+#[one_user]
+pub struct Framebuffer {
+    id: GLuint,
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.id);
+        }
+    }
+}
+
+/// Binds the default framebuffer ( the window ) so that rendering goes to the screen again
+#[inline]
+pub fn bind_default_framebuffer(_bn: &mut FramebufferBouncer) {
+    unsafe {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    }
+}
+
+impl Framebuffer {
+    pub fn new() -> UnboundFramebuffer {
+        let mut r = Framebuffer { id: 0 };
+        unsafe {
+            gl::GenFramebuffers(1, &mut r.id);
+        }
+        UnboundFramebuffer::from(r)
+    }
+
+    #[inline(always)]
+    fn bind_fb(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+        }
+    }
+
+    fn attach_texture_2d(&mut self, attachment: GLenum, tex: &Texture2D, level: usize) -> Result<(), String> {
+        if !tex.is_level_allocated(level) {
+            return Err(format!("Mip level {} of the texture has not been allocated!", level));
+        }
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        unsafe {
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, gl::TEXTURE_2D, tex.get_id(), level);
+        }
+        Ok(())
+    }
+
+    /// Attaches one layer of a mip level, cube map faces go through glFramebufferTexture2D since glFramebufferTextureLayer only takes them from opengl 4.5
+    fn attach_texture_layer<const N: usize, const TYP: GLenum>(&mut self, attachment: GLenum, tex: &Texture<N, TYP>, level: usize, layer: usize) -> Result<(), String>
+    where
+        Texture<N, TYP>: LayeredTexture,
+    {
+        let layers = unwrap_option_or_ret!(tex.get_num_of_layers(level), Err(format!("Mip level {} of the texture has not been allocated!", level)));
+        if layer >= layers {
+            return Err(format!("Layer {} is out of bounds, mip level {} of the texture only has {} layers!", layer, level, layers));
+        }
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            unsafe {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, CubeFace::ALL[layer].get_gl_target(), tex.get_id(), level);
+            }
+            return Ok(());
+        }
+        let layer: GLint = unwrap_result_or_ret!(layer.try_into(), Err("Layer too big for opengl!".to_owned()));
+        unsafe {
+            gl::FramebufferTextureLayer(gl::FRAMEBUFFER, attachment, tex.get_id(), level, layer);
+        }
+        Ok(())
+    }
+
+    /// Attaches all the layers of a mip level, which one gets rendered to is chosen by the geometry shader with gl_Layer
+    fn attach_texture_layered<const N: usize, const TYP: GLenum>(&mut self, attachment: GLenum, tex: &Texture<N, TYP>, level: usize) -> Result<(), String>
+    where
+        Texture<N, TYP>: LayeredTexture,
+    {
+        if tex.get_num_of_layers(level).is_none() {
+            return Err(format!("Mip level {} of the texture has not been allocated!", level));
+        }
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        unsafe {
+            gl::FramebufferTexture(gl::FRAMEBUFFER, attachment, tex.get_id(), level);
+        }
+        Ok(())
+    }
+
+    fn get_color_attachment_for<const N: usize, const TYP: GLenum>(index: u32, tex: &Texture<N, TYP>) -> Result<GLenum, String> {
+        if tex.has_depth_format() {
+            return Err("Textures with a depth format can't be used as color attachments!".to_owned());
+        }
+        Self::get_color_attachment(index)
+    }
+
+    /// The depth attachment, or the depth and stencil attachment if the texture has a combined depth and stencil format
+    fn get_depth_attachment_for<const N: usize, const TYP: GLenum>(tex: &Texture<N, TYP>) -> Result<GLenum, String> {
+        if !tex.has_depth_format() {
+            return Err("Only textures with a depth format can be used as depth attachments!".to_owned());
+        }
+        if tex.get_format() == Some(gl::DEPTH_STENCIL) {
+            Ok(gl::DEPTH_STENCIL_ATTACHMENT)
+        } else {
+            Ok(gl::DEPTH_ATTACHMENT)
+        }
+    }
+
+    fn get_color_attachment(index: u32) -> Result<GLenum, String> {
+        let mut max_attachments: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_COLOR_ATTACHMENTS, &mut max_attachments);
+        }
+        if i64::from(index) >= i64::from(max_attachments) {
+            return Err(format!("Color attachment {} is too big, the maximum number of color attachments is {}!", index, max_attachments));
+        }
+        Ok(gl::COLOR_ATTACHMENT0 + index)
+    }
+
+    pub fn attach_color_texture(&mut self, index: u32, tex: &Texture2D, level: usize) -> Result<(), String> {
+        self.attach_texture_2d(Self::get_color_attachment_for(index, tex)?, tex, level)
+    }
+
+    /// Attaches the texture as the depth attachment, or as the depth and stencil attachment if it has a combined depth and stencil format
+    pub fn attach_depth_texture(&mut self, tex: &Texture2D, level: usize) -> Result<(), String> {
+        self.attach_texture_2d(Self::get_depth_attachment_for(tex)?, tex, level)
+    }
+
+    /// Attaches one layer of an array texture, one slice of a 3D texture or one face of a cube map ( in CubeFace::ALL order )
+    pub fn attach_color_texture_layer<const N: usize, const TYP: GLenum>(&mut self, index: u32, tex: &Texture<N, TYP>, level: usize, layer: usize) -> Result<(), String>
+    where
+        Texture<N, TYP>: LayeredTexture,
+    {
+        self.attach_texture_layer(Self::get_color_attachment_for(index, tex)?, tex, level, layer)
+    }
+
+    /// Same as attach_color_texture_layer but for depth, for example to render one cascade of a shadow map
+    pub fn attach_depth_texture_layer<const N: usize, const TYP: GLenum>(&mut self, tex: &Texture<N, TYP>, level: usize, layer: usize) -> Result<(), String>
+    where
+        Texture<N, TYP>: LayeredTexture,
+    {
+        self.attach_texture_layer(Self::get_depth_attachment_for(tex)?, tex, level, layer)
+    }
+
+    #[inline(always)]
+    pub fn attach_color_cube_face(&mut self, index: u32, tex: &TextureCube, level: usize, face: CubeFace) -> Result<(), String> {
+        self.attach_color_texture_layer(index, tex, level, face.get_index())
+    }
+
+    #[inline(always)]
+    pub fn attach_depth_cube_face(&mut self, tex: &TextureCube, level: usize, face: CubeFace) -> Result<(), String> {
+        self.attach_depth_texture_layer(tex, level, face.get_index())
+    }
+
+    /// Attaches every layer of the texture at once for layered rendering
+    /// NOTE: Every attachment has to be layered for the framebuffer to be complete
+    pub fn attach_color_texture_layered<const N: usize, const TYP: GLenum>(&mut self, index: u32, tex: &Texture<N, TYP>, level: usize) -> Result<(), String>
+    where
+        Texture<N, TYP>: LayeredTexture,
+    {
+        self.attach_texture_layered(Self::get_color_attachment_for(index, tex)?, tex, level)
+    }
+
+    pub fn attach_depth_texture_layered<const N: usize, const TYP: GLenum>(&mut self, tex: &Texture<N, TYP>, level: usize) -> Result<(), String>
+    where
+        Texture<N, TYP>: LayeredTexture,
+    {
+        self.attach_texture_layered(Self::get_depth_attachment_for(tex)?, tex, level)
+    }
+
+    fn attach_renderbuffer(&mut self, attachment: GLenum, rb: &Renderbuffer) -> Result<(), String> {
+        if rb.get_internal_format().is_none() {
+            return Err("Renderbuffer has to be allocated before it can be attached!".to_owned());
+        }
+        unsafe {
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, rb.get_id());
+        }
+        Ok(())
+    }
+
+    pub fn attach_color_renderbuffer(&mut self, index: u32, rb: &Renderbuffer) -> Result<(), String> {
+        if rb.has_depth_format() {
+            return Err("Renderbuffers with a depth format can't be used as color attachments!".to_owned());
+        }
+        self.attach_renderbuffer(Self::get_color_attachment(index)?, rb)
+    }
+
+    /// Same as attach_depth_texture but for renderbuffers, which are enough when the depth doesn't need to be sampled afterwards
+    pub fn attach_depth_renderbuffer(&mut self, rb: &Renderbuffer) -> Result<(), String> {
+        if !rb.has_depth_format() {
+            return Err("Only renderbuffers with a depth format can be used as depth attachments!".to_owned());
+        }
+        let attachment = if rb.get_internal_format() == Some(gl::DEPTH24_STENCIL8) {
+            gl::DEPTH_STENCIL_ATTACHMENT
+        } else {
+            gl::DEPTH_ATTACHMENT
+        };
+        self.attach_renderbuffer(attachment, rb)
+    }
+
+    /// Needed for framebuffers without color attachments ( like shadow maps ) on older implementations
+    #[inline]
+    pub fn disable_color_buffers(&mut self) {
+        unsafe {
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+        }
+    }
+
+    pub fn check_status(&self) -> Result<(), String> {
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => Ok(()),
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Err("Framebuffer has an incomplete attachment!".to_owned()),
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Err("Framebuffer has no attachments!".to_owned()),
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => Err("Framebuffer draws to a color attachment that is missing!".to_owned()),
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => Err("Framebuffer reads from a color attachment that is missing!".to_owned()),
+            gl::FRAMEBUFFER_UNSUPPORTED => Err("Framebuffer attachment formats are not supported by this implementation!".to_owned()),
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Err("Framebuffer attachments have different numbers of samples!".to_owned()),
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Err("Framebuffer mixes layered and non layered attachments!".to_owned()),
+            _ => Err(format!("Framebuffer is incomplete, status: {:#x}!", status)),
+        }
+    }
+}
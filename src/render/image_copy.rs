@@ -0,0 +1,124 @@
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret};
+use gl::types::*;
+use std::convert::TryInto;
+
+pub(crate) mod sealed {
+    use gl::types::GLuint;
+
+    /// Keeps the ids of textures and renderbuffers out of the public interface of CopyableImage
+    pub trait CopyableImageId {
+        fn get_copy_id(&self) -> GLuint;
+    }
+}
+
+/// Textures and renderbuffers, anything glCopyImageSubData can copy between
+pub trait CopyableImage: sealed::CopyableImageId {
+    /// The target glCopyImageSubData expects for this kind of image
+    fn get_copy_target(&self) -> GLenum;
+
+    /// None if nothing has been allocated yet
+    fn get_copy_internal_format(&self) -> Option<GLenum>;
+
+    /// Width, height and depth ( or number of layers, or 6 faces for cube maps ) of a level, None if the level doesn't exist
+    fn get_copy_level_size(&self, level: usize) -> Option<[usize; 3]>;
+}
+
+/// Returns the width and height of the blocks of the format, uncompressed formats have blocks of one texel
+#[inline(always)]
+fn get_block_dims(internal_format: GLenum) -> [usize; 2] {
+    crate::get_compressed_block_info(internal_format).map_or([1, 1], |(block_w, block_h, _)| [block_w, block_h])
+}
+
+fn validate_copy_region(
+    side: &str,
+    level_size: Option<[usize; 3]>,
+    level: usize,
+    offset: [usize; 3],
+    size: [usize; 3],
+    block_dims: [usize; 2],
+) -> Result<(), String> {
+    let level_size = unwrap_option_or_ret!(level_size, Err(format!("Mip level {} of the {} does not exist!", level, side)));
+    for i in 0..3 {
+        let end = unwrap_option_or_ret!(offset[i].checked_add(size[i]), Err(format!("Region of the {} is too big!", side)));
+        // NOTE: Compressed images are made of whole blocks, so a region can reach the end of the last block even if the image ends before it
+        let (block_dim, fits) = match block_dims.get(i) {
+            Some(block_dim) => (*block_dim, end <= level_size[i].div_ceil(*block_dim) * block_dim),
+            None => (1, end <= level_size[i]),
+        };
+        if !fits {
+            return Err(format!("Region at offset {:?} of size {:?} does not fit in mip level {} of the {} which is of size {:?}!", offset, size, level, side, level_size));
+        }
+        if !offset[i].is_multiple_of(block_dim) || !(end.is_multiple_of(block_dim) || end == level_size[i]) {
+            return Err(format!("Region at offset {:?} of size {:?} of the {} is not aligned to the {} pixels * {} pixels blocks of its compressed format!", offset, size, side, block_dims[0], block_dims[1]));
+        }
+    }
+    Ok(())
+}
+
+fn to_gl_ints(vals: [usize; 3]) -> Result<[GLint; 3], String> {
+    let mut r: [GLint; 3] = [0; 3];
+    for i in 0..3 {
+        r[i] = unwrap_result_or_ret!(vals[i].try_into(), Err(format!("Value[{}] malformed!", i)));
+    }
+    Ok(r)
+}
+
+/// Copies a region of one image to another on the gpu, without converting anything, see crate::are_internal_formats_copy_compatible
+/// Offsets and sizes are in [x, y, z] order, where z is the layer for array textures and the face ( in CubeFace::ALL order ) for cube maps
+/// The size is in texels of the source, when copying between a compressed and an uncompressed image every block of one is a texel of the other
+/// NOTE: The images don't have to be bound, but copying between different levels of the same texture is not possible as it can't be borrowed twice
+pub fn copy_image_sub_data<S, D>(
+    src: &S,
+    src_level: usize,
+    src_offset: [usize; 3],
+    dst: &mut D,
+    dst_level: usize,
+    dst_offset: [usize; 3],
+    size: [usize; 3],
+) -> Result<(), String>
+where
+    S: CopyableImage,
+    D: CopyableImage,
+{
+    let src_format = unwrap_option_or_ret!(src.get_copy_internal_format(), Err("Source has to be allocated before it can be copied from!".to_owned()));
+    let dst_format = unwrap_option_or_ret!(dst.get_copy_internal_format(), Err("Destination has to be allocated before it can be copied to!".to_owned()));
+    if !crate::are_internal_formats_copy_compatible(src_format, dst_format) {
+        return Err(format!("Images with internal formats {:#x} and {:#x} can't be copied between!", src_format, dst_format));
+    }
+    let src_block_dims = get_block_dims(src_format);
+    let dst_block_dims = get_block_dims(dst_format);
+    let mut dst_size = size;
+    if src_block_dims != dst_block_dims {
+        for i in 0..2 {
+            dst_size[i] = size[i].div_ceil(src_block_dims[i]) * dst_block_dims[i];
+        }
+    }
+    validate_copy_region("source", src.get_copy_level_size(src_level), src_level, src_offset, size, src_block_dims)?;
+    validate_copy_region("destination", dst.get_copy_level_size(dst_level), dst_level, dst_offset, dst_size, dst_block_dims)?;
+
+    let src_offset = to_gl_ints(src_offset)?;
+    let dst_offset = to_gl_ints(dst_offset)?;
+    let size = to_gl_ints(size)?;
+    let src_level: GLint = unwrap_result_or_ret!(src_level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+    let dst_level: GLint = unwrap_result_or_ret!(dst_level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+    unsafe {
+        gl::CopyImageSubData(
+            src.get_copy_id(),
+            src.get_copy_target(),
+            src_level,
+            src_offset[0],
+            src_offset[1],
+            src_offset[2],
+            dst.get_copy_id(),
+            dst.get_copy_target(),
+            dst_level,
+            dst_offset[0],
+            dst_offset[1],
+            dst_offset[2],
+            size[0],
+            size[1],
+            size[2],
+        );
+    }
+    Ok(())
+}
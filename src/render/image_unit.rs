@@ -0,0 +1,199 @@
+use crate::render::texture::Texture;
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret};
+use gl::types::*;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::ops::{BitOr, BitOrAssign};
+use one_user::one_user;
+
+/// What shaders are allowed to do with an image
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl ImageAccess {
+    #[inline(always)]
+    pub fn get_gl_enum(self) -> GLenum {
+        match self {
+            ImageAccess::ReadOnly => gl::READ_ONLY,
+            ImageAccess::WriteOnly => gl::WRITE_ONLY,
+            ImageAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+/// The internal formats shaders can load from and store to images as ( the format qualifier in glsl )
+pub fn is_image_format(internal_format: GLenum) -> bool {
+    matches!(
+        internal_format,
+        gl::RGBA32F | gl::RGBA16F | gl::RG32F | gl::RG16F | gl::R11F_G11F_B10F | gl::R32F | gl::R16F
+            | gl::RGBA32UI | gl::RGBA16UI | gl::RGB10_A2UI | gl::RGBA8UI | gl::RG32UI | gl::RG16UI | gl::RG8UI | gl::R32UI | gl::R16UI | gl::R8UI
+            | gl::RGBA32I | gl::RGBA16I | gl::RGBA8I | gl::RG32I | gl::RG16I | gl::RG8I | gl::R32I | gl::R16I | gl::R8I
+            | gl::RGBA16 | gl::RGB10_A2 | gl::RGBA8 | gl::RG16 | gl::RG8 | gl::R16 | gl::R8
+            | gl::RGBA16_SNORM | gl::RGBA8_SNORM | gl::RG16_SNORM | gl::RG8_SNORM | gl::R16_SNORM | gl::R8_SNORM
+    )
+}
+
+impl<'a> textureimage_binder::OnBind for TextureImage<'a> {
+    #[inline(always)]
+    fn on_bind<const SLOT: usize>(&self) {
+        unsafe {
+            gl::BindImageTexture(
+                SLOT as GLuint,
+                self.tex_id,
+                self.level,
+                if self.layer.is_none() { gl::TRUE } else { gl::FALSE },
+                self.layer.unwrap_or(0),
+                self.access.get_gl_enum(),
+                self.format,
+            );
+        }
+    }
+}
+
+/// A mip level ( or one layer of it ) of a texture that shaders can load from and store to through an image unit
+/// NOTE: Binding it to an image unit doesn't change which textures are bound to texture units, so it doesn't disturb TextureBouncers
+#[one_user(8)]
+pub struct TextureImage<'a> {
+    tex_id: GLuint,
+    level: GLint,
+    /// None means every layer is bound ( layered ), textures without layers are always bound whole
+    layer: Option<GLint>,
+    access: ImageAccess,
+    format: GLenum,
+    _tex: PhantomData<&'a ()>,
+}
+
+impl<'a> TextureImage<'a> {
+    /// The format has to be an image format ( see is_image_format ) with texels as big as the ones of the texture, it's what loads and stores convert from and to
+    /// A layer can only be chosen for textures that have layers ( arrays, 3D textures and cube maps ), otherwise all of them are bound
+    pub fn new<const N: usize, const TYP: GLenum>(
+        tex: &'a Texture<N, TYP>,
+        level: usize,
+        layer: Option<usize>,
+        access: ImageAccess,
+        format: GLenum,
+    ) -> Result<UnboundTextureImage<'a>, String> {
+        let tex_format = unwrap_option_or_ret!(tex.get_internal_format(), Err("Texture has to be allocated before it can be used as an image!".to_owned()));
+        let tex_format: GLenum = unwrap_result_or_ret!(tex_format.try_into(), Err("Internal format of texture malformed!".to_owned()));
+        if !is_image_format(format) {
+            return Err(format!("Format {:#x} can't be used for images!", format));
+        }
+        if crate::is_compressed_internal_format(tex_format) || crate::is_depth_internal_format(tex_format) {
+            return Err("Compressed textures and textures with a depth format can't be used as images!".to_owned());
+        }
+        // NOTE: Opengl matches the formats by size by default ( GL_IMAGE_FORMAT_COMPATIBILITY_BY_SIZE )
+        if crate::get_internal_format_texel_size(tex_format) != crate::get_internal_format_texel_size(format) {
+            return Err(format!("Image format {:#x} does not have texels of the same size as the internal format of the texture ({:#x})!", format, tex_format));
+        }
+        let level_size = unwrap_option_or_ret!(tex.get_level_size(level), Err(format!("Mip level {} of the texture has not been allocated!", level)));
+        let layer = match layer {
+            Some(layer) => {
+                let layers = match TYP {
+                    gl::TEXTURE_1D_ARRAY => level_size.get(1).copied(),
+                    gl::TEXTURE_2D_ARRAY | gl::TEXTURE_3D | gl::TEXTURE_CUBE_MAP_ARRAY => level_size.get(2).copied(),
+                    gl::TEXTURE_CUBE_MAP => Some(6),
+                    _ => None,
+                };
+                let layers = unwrap_option_or_ret!(layers, Err("Only textures with layers can have a single layer bound as an image!".to_owned()));
+                if layer >= layers {
+                    return Err(format!("Layer {} is out of bounds, mip level {} of the texture only has {} layers!", layer, level, layers));
+                }
+                Some(unwrap_result_or_ret!(layer.try_into(), Err("Layer too big for opengl!".to_owned())))
+            }
+            None => None,
+        };
+        Ok(UnboundTextureImage::from(TextureImage {
+            tex_id: tex.get_id(),
+            level: unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned())),
+            layer,
+            access,
+            format,
+            _tex: PhantomData,
+        }))
+    }
+
+    #[inline(always)]
+    pub fn get_access(&self) -> ImageAccess {
+        self.access
+    }
+
+    #[inline(always)]
+    pub fn get_format(&self) -> GLenum {
+        self.format
+    }
+}
+
+/// Which kinds of accesses made after the barrier have to see the writes shaders made to images and buffers before it, combine them with |
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryBarrier(GLbitfield);
+
+impl MemoryBarrier {
+    pub const VERTEX_ATTRIB_ARRAY: MemoryBarrier = MemoryBarrier(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    pub const ELEMENT_ARRAY: MemoryBarrier = MemoryBarrier(gl::ELEMENT_ARRAY_BARRIER_BIT);
+    pub const UNIFORM: MemoryBarrier = MemoryBarrier(gl::UNIFORM_BARRIER_BIT);
+    pub const TEXTURE_FETCH: MemoryBarrier = MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT);
+    pub const SHADER_IMAGE_ACCESS: MemoryBarrier = MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    pub const COMMAND: MemoryBarrier = MemoryBarrier(gl::COMMAND_BARRIER_BIT);
+    pub const PIXEL_BUFFER: MemoryBarrier = MemoryBarrier(gl::PIXEL_BUFFER_BARRIER_BIT);
+    pub const TEXTURE_UPDATE: MemoryBarrier = MemoryBarrier(gl::TEXTURE_UPDATE_BARRIER_BIT);
+    pub const BUFFER_UPDATE: MemoryBarrier = MemoryBarrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+    pub const FRAMEBUFFER: MemoryBarrier = MemoryBarrier(gl::FRAMEBUFFER_BARRIER_BIT);
+    pub const TRANSFORM_FEEDBACK: MemoryBarrier = MemoryBarrier(gl::TRANSFORM_FEEDBACK_BARRIER_BIT);
+    pub const ATOMIC_COUNTER: MemoryBarrier = MemoryBarrier(gl::ATOMIC_COUNTER_BARRIER_BIT);
+    pub const SHADER_STORAGE: MemoryBarrier = MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+    pub const CLIENT_MAPPED_BUFFER: MemoryBarrier = MemoryBarrier(gl::CLIENT_MAPPED_BUFFER_BARRIER_BIT);
+    pub const QUERY_BUFFER: MemoryBarrier = MemoryBarrier(gl::QUERY_BUFFER_BARRIER_BIT);
+    pub const ALL: MemoryBarrier = MemoryBarrier(gl::ALL_BARRIER_BITS);
+
+    #[inline(always)]
+    pub const fn empty() -> Self {
+        MemoryBarrier(0)
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline(always)]
+    pub const fn contains(self, other: MemoryBarrier) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline(always)]
+    pub const fn get_bits(self) -> GLbitfield {
+        self.0
+    }
+}
+
+impl BitOr for MemoryBarrier {
+    type Output = MemoryBarrier;
+
+    #[inline(always)]
+    fn bitor(self, rhs: MemoryBarrier) -> MemoryBarrier {
+        MemoryBarrier(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MemoryBarrier {
+    #[inline(always)]
+    fn bitor_assign(&mut self, rhs: MemoryBarrier) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Waits for the writes shaders made before it to be visible to the accesses in barriers
+/// NOTE: An empty barrier does nothing, so it's not sent to opengl
+#[inline]
+pub fn memory_barrier(barriers: MemoryBarrier) {
+    if barriers.is_empty() {
+        return;
+    }
+    unsafe {
+        gl::MemoryBarrier(barriers.get_bits());
+    }
+}
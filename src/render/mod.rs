@@ -1,3 +1,11 @@
+pub mod bindless;
+pub mod framebuffer;
+pub mod image_copy;
+pub mod image_unit;
 pub mod program;
+pub mod renderbuffer;
+pub mod sampler;
 pub mod shader;
 pub mod texture;
+pub mod texture_buffer;
+pub mod texture_unit;
@@ -1,3 +1,4 @@
+use crate::render::bindless::ResidentTextureHandle;
 use crate::render::shader::*;
 use crate::unwrap_result_or_ret;
 use gl::types::*;
@@ -213,6 +214,14 @@ impl<'a> Program<'a> {
         }
     }
 
+    /// NOTE: Needs ARB_bindless_texture, the uniform has to be a sampler declared with the bindless_sampler layout qualifier ( or a uvec2 )
+    #[inline]
+    pub fn set_uniform_texture_handle(&mut self, id: GLint, handle: &ResidentTextureHandle) {
+        unsafe {
+            crate::gl_ext::UniformHandleui64ARB(id, handle.get_handle());
+        }
+    }
+
     #[inline]
     pub fn get_attribute_hashmap(&self) -> &HashMap<&str, GLuint> {
         &self.attrib_ids
@@ -0,0 +1,129 @@
+use crate::render::image_copy::{sealed, CopyableImage};
+use crate::unwrap_result_or_ret;
+use gl::types::*;
+use std::convert::TryInto;
+use one_user::one_user;
+
+impl renderbuffer_binder::OnBind for Renderbuffer {
+    #[inline(always)]
+    fn on_bind<const BI: usize>(&self) {
+        unsafe {
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self.id);
+        }
+    }
+}
+
+/// An image that can only be rendered to ( as a framebuffer attachment ) and copied from, unlike a texture it can't be sampled from
+#[one_user]
+pub struct Renderbuffer {
+    id: GLuint,
+    /// All zeroes until it has been allocated
+    size: [usize; 2],
+    /// None until it has been allocated
+    internal_format: Option<GLenum>,
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteRenderbuffers(1, &self.id);
+        }
+    }
+}
+
+impl Renderbuffer {
+    pub fn new() -> UnboundRenderbuffer {
+        let mut r = Renderbuffer {
+            id: 0,
+            size: [0; 2],
+            internal_format: None,
+        };
+        unsafe {
+            gl::GenRenderbuffers(1, &mut r.id);
+        }
+        UnboundRenderbuffer::from(r)
+    }
+
+    /// The contents are undefined until something is rendered to the renderbuffer
+    pub fn allocate_storage(&mut self, internal_format: GLenum, size: [usize; 2]) -> Result<(), String> {
+        if crate::gl_internal_format_to_format(internal_format).is_none() {
+            return Err(format!("Unsupported internal format: {:#x}!", internal_format));
+        }
+        let mut max_size: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_RENDERBUFFER_SIZE, &mut max_size);
+        }
+        let max_size: usize = unwrap_result_or_ret!(max_size.try_into(), Err("Max renderbuffer size returned from opengl is negative, faulty opengl implementation!".to_owned()));
+        if size.contains(&0) || size[0] > max_size || size[1] > max_size {
+            return Err(format!("Renderbuffers have to be between 1 and {} pixels wide and high, not {} pixels * {} pixels!", max_size, size[0], size[1]));
+        }
+        let w: GLsizei = unwrap_result_or_ret!(size[0].try_into(), Err("Size[0] malformed!".to_owned()));
+        let h: GLsizei = unwrap_result_or_ret!(size[1].try_into(), Err("Size[1] malformed!".to_owned()));
+        unsafe {
+            gl::RenderbufferStorage(gl::RENDERBUFFER, internal_format, w, h);
+        }
+        self.size = size;
+        self.internal_format = Some(internal_format);
+        Ok(())
+    }
+
+    pub fn with_storage(bn: &mut RenderbufferBouncer, internal_format: GLenum, size: [usize; 2]) -> Result<UnboundRenderbuffer, String> {
+        let mut r = Self::new();
+        {
+            let mut r = r.bind_mut(bn);
+            r.allocate_storage(internal_format, size)?;
+        }
+        Ok(r)
+    }
+
+    /// All zeroes if nothing has been allocated yet
+    #[inline(always)]
+    pub fn get_size(&self) -> [usize; 2] {
+        self.size
+    }
+
+    /// None if nothing has been allocated yet
+    #[inline(always)]
+    pub fn get_internal_format(&self) -> Option<GLenum> {
+        self.internal_format
+    }
+
+    /// True if the renderbuffer has a depth or a combined depth and stencil internal format
+    #[inline(always)]
+    pub fn has_depth_format(&self) -> bool {
+        self.internal_format.is_some_and(crate::is_depth_internal_format)
+    }
+
+    // NEEDED BY Framebuffer
+    #[inline(always)]
+    pub(crate) fn get_id(&self) -> GLuint {
+        self.id
+    }
+}
+
+impl sealed::CopyableImageId for Renderbuffer {
+    #[inline(always)]
+    fn get_copy_id(&self) -> GLuint {
+        self.id
+    }
+}
+
+impl CopyableImage for Renderbuffer {
+    #[inline(always)]
+    fn get_copy_target(&self) -> GLenum {
+        gl::RENDERBUFFER
+    }
+
+    #[inline(always)]
+    fn get_copy_internal_format(&self) -> Option<GLenum> {
+        self.internal_format
+    }
+
+    /// Renderbuffers only have one level
+    fn get_copy_level_size(&self, level: usize) -> Option<[usize; 3]> {
+        if self.internal_format.is_none() || level != 0 {
+            return None;
+        }
+        Some([self.size[0], self.size[1], 1])
+    }
+}
@@ -0,0 +1,234 @@
+use crate::render::bindless::ResidentHandles;
+use crate::unwrap_result_or_ret;
+use gl::types::*;
+use std::convert::TryInto;
+use one_user::one_user;
+
+impl sampler_binder::OnBind for Sampler {
+    #[inline(always)]
+    fn on_bind<const SLOT: usize>(&self) {
+        // NOTE: Unlike textures samplers are bound directly to a unit so there is no need to change the active texture unit
+        unsafe {
+            gl::BindSampler(SLOT as GLuint, self.id);
+        }
+    }
+}
+
+/// While bound to a texture unit a sampler overrides the filtering, wrapping and comparison parameters of whatever texture is bound to that unit
+/// so the same texture can be sampled in different ways by binding it to multiple units with different samplers
+#[one_user(256)]
+pub struct Sampler {
+    id: GLuint,
+    /// The bindless handles made from this sampler that are still resident
+    resident_handles: ResidentHandles,
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        // Handles made from the sampler become invalid once it's deleted
+        self.resident_handles.release_all();
+        unsafe {
+            gl::DeleteSamplers(1, &self.id);
+        }
+    }
+}
+
+impl Sampler {
+    /// Same as SamplerBuilder::new().build()
+    pub fn new() -> UnboundSampler {
+        SamplerBuilder::new().build().expect("Building default sampler!")
+    }
+
+    // NEEDED BY Texture
+    #[inline(always)]
+    pub(crate) fn get_id(&self) -> GLuint {
+        self.id
+    }
+
+    // NEEDED BY Texture
+    #[inline(always)]
+    pub(crate) fn get_resident_handles(&self) -> &ResidentHandles {
+        &self.resident_handles
+    }
+}
+
+/// Unbinds any sampler from the texture unit so the parameters of the texture bound to it are used again
+#[inline]
+pub fn unbind_sampler<const SLOT: usize>(_bn: &mut SamplerBouncer<SLOT>) {
+    unsafe {
+        gl::BindSampler(SLOT as GLuint, 0);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SamplerBuilder {
+    min_filter: GLenum,
+    mag_filter: GLenum,
+    wrap: [GLenum; 3],
+    max_anisotropy: f32,
+    lod_range: (f32, f32),
+    lod_bias: f32,
+    compare_func: Option<GLenum>,
+    border_color: [f32; 4],
+}
+
+impl Default for SamplerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SamplerBuilder {
+    /// Same defaults as opengl, except the min filter, which is linear for the same reason as in Texture::new
+    pub fn new() -> Self {
+        SamplerBuilder {
+            min_filter: gl::LINEAR,
+            mag_filter: gl::LINEAR,
+            wrap: [gl::REPEAT; 3],
+            max_anisotropy: 1.0,
+            lod_range: (-1000.0, 1000.0),
+            lod_bias: 0.0,
+            compare_func: None,
+            border_color: [0.0; 4],
+        }
+    }
+
+    pub fn with_min_filter(mut self, min_filter: GLenum) -> Self {
+        self.min_filter = min_filter;
+        self
+    }
+
+    pub fn with_mag_filter(mut self, mag_filter: GLenum) -> Self {
+        self.mag_filter = mag_filter;
+        self
+    }
+
+    pub fn with_x_wrap(mut self, wrap_x: GLenum) -> Self {
+        self.wrap[0] = wrap_x;
+        self
+    }
+
+    pub fn with_y_wrap(mut self, wrap_y: GLenum) -> Self {
+        self.wrap[1] = wrap_y;
+        self
+    }
+
+    pub fn with_z_wrap(mut self, wrap_z: GLenum) -> Self {
+        self.wrap[2] = wrap_z;
+        self
+    }
+
+    /// 1.0 means no anisotropic filtering, anything else has to be at most crate::get_max_anisotropy()
+    pub fn with_max_anisotropy(mut self, max_anisotropy: f32) -> Self {
+        self.max_anisotropy = max_anisotropy;
+        self
+    }
+
+    pub fn with_lod_range(mut self, min_lod: f32, max_lod: f32) -> Self {
+        self.lod_range = (min_lod, max_lod);
+        self
+    }
+
+    pub fn with_lod_bias(mut self, lod_bias: f32) -> Self {
+        self.lod_bias = lod_bias;
+        self
+    }
+
+    /// Some(func) makes the sampler compare the reference value against the depth texture using func ( for shadow samplers ), None disables comparison
+    pub fn with_compare_func(mut self, compare_func: Option<GLenum>) -> Self {
+        self.compare_func = compare_func;
+        self
+    }
+
+    /// The color used when sampling outside the texture with the CLAMP_TO_BORDER wrap mode
+    pub fn with_border_color(mut self, border_color: [f32; 4]) -> Self {
+        self.border_color = border_color;
+        self
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !matches!(
+            self.min_filter,
+            gl::NEAREST
+                | gl::LINEAR
+                | gl::NEAREST_MIPMAP_NEAREST
+                | gl::LINEAR_MIPMAP_NEAREST
+                | gl::NEAREST_MIPMAP_LINEAR
+                | gl::LINEAR_MIPMAP_LINEAR
+        ) {
+            return Err(format!("Invalid min filter: {:#x}!", self.min_filter));
+        }
+        if !matches!(self.mag_filter, gl::NEAREST | gl::LINEAR) {
+            return Err(format!("Invalid mag filter: {:#x}!", self.mag_filter));
+        }
+        for wrap in self.wrap.iter() {
+            if !matches!(
+                *wrap,
+                gl::REPEAT
+                    | gl::MIRRORED_REPEAT
+                    | gl::CLAMP_TO_EDGE
+                    | gl::CLAMP_TO_BORDER
+                    | gl::MIRROR_CLAMP_TO_EDGE
+            ) {
+                return Err(format!("Invalid wrap mode: {:#x}!", wrap));
+            }
+        }
+        crate::validate_max_anisotropy(self.max_anisotropy)?;
+        if self.lod_range.0.is_nan() || self.lod_range.1.is_nan() || self.lod_range.0 > self.lod_range.1 {
+            return Err("Min lod has to be smaller or equal to max lod!".to_owned());
+        }
+        if let Some(func) = self.compare_func {
+            if !matches!(
+                func,
+                gl::LEQUAL
+                    | gl::GEQUAL
+                    | gl::LESS
+                    | gl::GREATER
+                    | gl::EQUAL
+                    | gl::NOTEQUAL
+                    | gl::ALWAYS
+                    | gl::NEVER
+            ) {
+                return Err(format!("Invalid compare function: {:#x}!", func));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn build(&self) -> Result<UnboundSampler, String> {
+        self.validate()?;
+        let mut r = Sampler {
+            id: 0,
+            resident_handles: ResidentHandles::default(),
+        };
+        unsafe {
+            gl::GenSamplers(1, &mut r.id);
+        }
+        let to_glint = |e: GLenum| -> Result<GLint, String> {
+            Ok(unwrap_result_or_ret!(e.try_into(), Err("Converting to gl types!".to_owned())))
+        };
+        unsafe {
+            gl::SamplerParameteri(r.id, gl::TEXTURE_MIN_FILTER, to_glint(self.min_filter)?);
+            gl::SamplerParameteri(r.id, gl::TEXTURE_MAG_FILTER, to_glint(self.mag_filter)?);
+            gl::SamplerParameteri(r.id, gl::TEXTURE_WRAP_S, to_glint(self.wrap[0])?);
+            gl::SamplerParameteri(r.id, gl::TEXTURE_WRAP_T, to_glint(self.wrap[1])?);
+            gl::SamplerParameteri(r.id, gl::TEXTURE_WRAP_R, to_glint(self.wrap[2])?);
+            // Only set when used because the enum is not valid on implementations that don't support anisotropic filtering
+            if self.max_anisotropy != 1.0 {
+                gl::SamplerParameterf(r.id, crate::GL_TEXTURE_MAX_ANISOTROPY, self.max_anisotropy);
+            }
+            gl::SamplerParameterf(r.id, gl::TEXTURE_MIN_LOD, self.lod_range.0);
+            gl::SamplerParameterf(r.id, gl::TEXTURE_MAX_LOD, self.lod_range.1);
+            gl::SamplerParameterf(r.id, gl::TEXTURE_LOD_BIAS, self.lod_bias);
+            gl::SamplerParameterfv(r.id, gl::TEXTURE_BORDER_COLOR, self.border_color.as_ptr());
+            match self.compare_func {
+                Some(func) => {
+                    gl::SamplerParameteri(r.id, gl::TEXTURE_COMPARE_MODE, to_glint(gl::COMPARE_REF_TO_TEXTURE)?);
+                    gl::SamplerParameteri(r.id, gl::TEXTURE_COMPARE_FUNC, to_glint(func)?);
+                }
+                None => gl::SamplerParameteri(r.id, gl::TEXTURE_COMPARE_MODE, to_glint(gl::NONE)?),
+            }
+        }
+        Ok(UnboundSampler::from(r))
+    }
+}
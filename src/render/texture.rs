@@ -1,8 +1,15 @@
+use crate::render::bindless::{self, ResidentHandles, ResidentTextureHandle};
+use crate::render::image_copy::{sealed, CopyableImage};
+use crate::render::sampler::Sampler;
+use crate::render::texture_unit::UnitBinding;
 use crate::unwrap_option_or_ret;
 use crate::unwrap_result_or_ret;
 use crate::HasGLEnum;
 use gl::types::*;
+use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::ops::Range;
+use std::sync::Arc;
 
 
 #[inline(always)]
@@ -17,6 +24,16 @@ unsafe fn internal_gl_tex_image<const N: usize>(
     data: *const GLvoid,
 ) {
     match N {
+        1 => gl::TexImage1D(
+            target,
+            level,
+            internal_format,
+            dim[0],
+            border,
+            format,
+            typ,
+            data,
+        ),
         2 => gl::TexImage2D(
             target,
             level,
@@ -44,20 +61,504 @@ unsafe fn internal_gl_tex_image<const N: usize>(
     }
 }
 
+#[inline(always)]
+unsafe fn internal_gl_tex_sub_image<const N: usize>(
+    target: GLenum,
+    level: GLint,
+    offset: [GLint; N],
+    dim: [GLsizei; N],
+    format: GLenum,
+    typ: GLenum,
+    data: *const GLvoid,
+) {
+    match N {
+        1 => gl::TexSubImage1D(target, level, offset[0], dim[0], format, typ, data),
+        2 => gl::TexSubImage2D(
+            target, level, offset[0], offset[1], dim[0], dim[1], format, typ, data,
+        ),
+        3 => gl::TexSubImage3D(
+            target, level, offset[0], offset[1], offset[2], dim[0], dim[1], dim[2], format, typ,
+            data,
+        ),
+        _ => panic!("Unspported dimensions for texture!"),
+    }
+}
+
+#[inline(always)]
+unsafe fn internal_gl_tex_storage<const N: usize>(
+    target: GLenum,
+    levels: GLsizei,
+    internal_format: GLenum,
+    dim: [GLsizei; N],
+) {
+    match N {
+        1 => gl::TexStorage1D(target, levels, internal_format, dim[0]),
+        2 => gl::TexStorage2D(target, levels, internal_format, dim[0], dim[1]),
+        3 => gl::TexStorage3D(target, levels, internal_format, dim[0], dim[1], dim[2]),
+        _ => panic!("Unspported dimensions for texture!"),
+    }
+}
+
+#[inline(always)]
+unsafe fn internal_gl_compressed_tex_image<const N: usize>(
+    target: GLenum,
+    level: GLint,
+    internal_format: GLenum,
+    dim: [GLsizei; N],
+    image_size: GLsizei,
+    data: *const GLvoid,
+) {
+    match N {
+        1 => gl::CompressedTexImage1D(target, level, internal_format, dim[0], 0, image_size, data),
+        2 => gl::CompressedTexImage2D(
+            target, level, internal_format, dim[0], dim[1], 0, image_size, data,
+        ),
+        3 => gl::CompressedTexImage3D(
+            target, level, internal_format, dim[0], dim[1], dim[2], 0, image_size, data,
+        ),
+        _ => panic!("Unspported dimensions for texture!"),
+    }
+}
+
+#[inline(always)]
+unsafe fn internal_gl_compressed_tex_sub_image<const N: usize>(
+    target: GLenum,
+    level: GLint,
+    offset: [GLint; N],
+    dim: [GLsizei; N],
+    format: GLenum,
+    image_size: GLsizei,
+    data: *const GLvoid,
+) {
+    match N {
+        1 => gl::CompressedTexSubImage1D(target, level, offset[0], dim[0], format, image_size, data),
+        2 => gl::CompressedTexSubImage2D(
+            target, level, offset[0], offset[1], dim[0], dim[1], format, image_size, data,
+        ),
+        3 => gl::CompressedTexSubImage3D(
+            target, level, offset[0], offset[1], offset[2], dim[0], dim[1], dim[2], format,
+            image_size, data,
+        ),
+        _ => panic!("Unspported dimensions for texture!"),
+    }
+}
+
+/// The faces of a cube map, in the order opengl lays them out in cube map arrays
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    #[inline(always)]
+    pub fn get_gl_target(self) -> GLenum {
+        match self {
+            CubeFace::PositiveX => gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+            CubeFace::NegativeX => gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+            CubeFace::PositiveY => gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+            CubeFace::NegativeY => gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            CubeFace::PositiveZ => gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+            CubeFace::NegativeZ => gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        }
+    }
+
+    /// The position of the face in CubeFace::ALL, which is also its layer in a cube map
+    #[inline(always)]
+    pub fn get_index(self) -> usize {
+        (self.get_gl_target() - gl::TEXTURE_CUBE_MAP_POSITIVE_X) as usize
+    }
+}
+
+/// Textures made of layers ( array layers, 3D slices or cube faces ) which can be attached to a framebuffer one layer at a time or all at once for layered rendering
+pub trait LayeredTexture {
+    /// The number of layers of a mip level, None if the level has not been allocated
+    /// NOTE: For cube map arrays these are layer-faces, for 3D textures the number of slices shrinks with every level
+    fn get_num_of_layers(&self, level: usize) -> Option<usize>;
+}
+
+/// Where a channel of a texture gets its value from when it's sampled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Swizzle {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+    Zero,
+    One,
+}
+
+impl Swizzle {
+    /// The swizzle opengl uses by default, every channel reads from itself
+    pub const IDENTITY: [Swizzle; 4] = [Swizzle::Red, Swizzle::Green, Swizzle::Blue, Swizzle::Alpha];
+
+    #[inline(always)]
+    pub fn get_gl_enum(self) -> GLenum {
+        match self {
+            Swizzle::Red => gl::RED,
+            Swizzle::Green => gl::GREEN,
+            Swizzle::Blue => gl::BLUE,
+            Swizzle::Alpha => gl::ALPHA,
+            Swizzle::Zero => gl::ZERO,
+            Swizzle::One => gl::ONE,
+        }
+    }
+}
+
+/// Describes how the pixels of an upload are laid out in memory, so a region can be uploaded straight out of a bigger image without copying it first
+/// The default is tightly packed data, which is how opengl is set up by crate::init
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelStore {
+    alignment: usize,
+    row_length: usize,
+    image_height: usize,
+    skip_pixels: usize,
+    skip_rows: usize,
+    skip_images: usize,
+}
+
+impl Default for PixelStore {
+    fn default() -> Self {
+        Self::TIGHTLY_PACKED
+    }
+}
+
+impl PixelStore {
+    pub const TIGHTLY_PACKED: PixelStore = PixelStore {
+        alignment: 1,
+        row_length: 0,
+        image_height: 0,
+        skip_pixels: 0,
+        skip_rows: 0,
+        skip_images: 0,
+    };
+
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::TIGHTLY_PACKED
+    }
+
+    /// Rows start at multiples of alignment bytes ( 1, 2, 4 or 8 )
+    pub fn with_alignment(mut self, alignment: usize) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// The width in pixels of the whole image the region is in, 0 means rows are as wide as the region
+    pub fn with_row_length(mut self, row_length: usize) -> Self {
+        self.row_length = row_length;
+        self
+    }
+
+    /// The height in rows of the whole image the region is in ( only used for 3D uploads ), 0 means images are as high as the region
+    pub fn with_image_height(mut self, image_height: usize) -> Self {
+        self.image_height = image_height;
+        self
+    }
+
+    /// The x position of the region in the whole image
+    pub fn with_skip_pixels(mut self, skip_pixels: usize) -> Self {
+        self.skip_pixels = skip_pixels;
+        self
+    }
+
+    /// The y position of the region in the whole image
+    pub fn with_skip_rows(mut self, skip_rows: usize) -> Self {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    /// The z position of the region in the whole image ( only used for 3D uploads )
+    pub fn with_skip_images(mut self, skip_images: usize) -> Self {
+        self.skip_images = skip_images;
+        self
+    }
+
+    /// Returns the number of elements a slice needs to hold a region of size laid out like this
+    /// NOTE: Like opengl, rows and images are only taken into account for regions that have them
+    fn get_required_len(&self, size: &[usize], epp: u8, element_size: usize) -> Result<usize, String> {
+        if !matches!(self.alignment, 1 | 2 | 4 | 8) {
+            return Err(format!("Alignment has to be 1, 2, 4 or 8, not {}!", self.alignment));
+        }
+        if size.contains(&0) || element_size == 0 {
+            return Ok(0);
+        }
+        let dim = |i: usize| size.get(i).copied().unwrap_or(1);
+        let (width, height, depth) = (dim(0), dim(1), dim(2));
+        let row_pixels = if self.row_length == 0 { width } else { self.row_length };
+        let image_rows = if self.image_height == 0 { height } else { self.image_height };
+        if size.len() >= 2 && self.skip_pixels.checked_add(width).is_none_or(|end| end > row_pixels) {
+            return Err(format!("Rows of {} pixels can't hold {} pixels after skipping {} pixels!", row_pixels, width, self.skip_pixels));
+        }
+        if size.len() >= 3 && self.skip_rows.checked_add(height).is_none_or(|end| end > image_rows) {
+            return Err(format!("Images of {} rows can't hold {} rows after skipping {} rows!", image_rows, height, self.skip_rows));
+        }
+
+        let too_big = || "Layout describes more data than fits in memory!".to_owned();
+        let pixel_bytes = usize::from(epp) * element_size;
+        let row_bytes = unwrap_option_or_ret!(row_pixels.checked_mul(pixel_bytes), Err(too_big()));
+        // Rows are only padded to the alignment when the elements are smaller than it
+        let row_stride = if element_size < self.alignment {
+            unwrap_option_or_ret!(row_bytes.checked_next_multiple_of(self.alignment), Err(too_big()))
+        } else {
+            row_bytes
+        };
+        let image_stride = unwrap_option_or_ret!(row_stride.checked_mul(image_rows), Err(too_big()));
+        let (skip_rows, skip_images) = (
+            if size.len() >= 2 { self.skip_rows } else { 0 },
+            if size.len() >= 3 { self.skip_images } else { 0 },
+        );
+        // The last byte read is in the last pixel of the last row of the last image
+        let end = [
+            (Some(self.skip_pixels), pixel_bytes),
+            (skip_rows.checked_add(height - 1), row_stride),
+            (skip_images.checked_add(depth - 1), image_stride),
+            (Some(width), pixel_bytes),
+        ]
+        .iter()
+        .try_fold(0usize, |acc, (count, stride)| acc.checked_add(count.as_ref()?.checked_mul(*stride)?));
+        let end = unwrap_option_or_ret!(end, Err(too_big()));
+        Ok(end.div_ceil(element_size))
+    }
+
+    /// Sets the unpack state opengl reads uploads with
+    fn apply_unpack(&self) -> Result<(), String> {
+        let params = [
+            (gl::UNPACK_ALIGNMENT, self.alignment),
+            (gl::UNPACK_ROW_LENGTH, self.row_length),
+            (gl::UNPACK_IMAGE_HEIGHT, self.image_height),
+            (gl::UNPACK_SKIP_PIXELS, self.skip_pixels),
+            (gl::UNPACK_SKIP_ROWS, self.skip_rows),
+            (gl::UNPACK_SKIP_IMAGES, self.skip_images),
+        ];
+        for (param, val) in params {
+            let val: GLint = unwrap_result_or_ret!(val.try_into(), Err(format!("Unpack parameter {:#x} too big for opengl!", param)));
+            unsafe {
+                gl::PixelStorei(param, val);
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the upload with this unpack state and puts the state back to tightly packed afterwards, so every other upload keeps working as expected
+    fn with_unpack_state<F: FnOnce()>(&self, upload: F) -> Result<(), String> {
+        if *self == Self::TIGHTLY_PACKED {
+            upload();
+            return Ok(());
+        }
+        self.apply_unpack()?;
+        upload();
+        Self::TIGHTLY_PACKED.apply_unpack()
+    }
+}
+
+/// NOTE: This is global state, when enabled cube map lookups near the edge of a face also sample the neighbouring faces
+#[inline]
+pub fn set_seamless_cube_map_filtering(enabled: bool) {
+    unsafe {
+        if enabled {
+            gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+        } else {
+            gl::Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+        }
+    }
+}
+
 mod priv_texture{
 use super::*;
 use gl::types::{GLenum, GLuint};
 use one_user::one_user;
 
 
+/// Returns the sized internal format to store the data as and the number of elements per pixel of the data
+/// If no internal format is provided the default one for the type and format of the data is used
+fn get_gl_format_of<ET>(internal_format: Option<GLenum>, format: GLenum) -> Result<(GLint, u8), String>
+where
+    ET: HasGLEnum,
+{
+    let internal_format = unwrap_option_or_ret!(
+        internal_format,
+        Ok(unwrap_option_or_ret!(
+            crate::format_to_gl_internal_format(ET::get_gl_type(), format),
+            Err("Invalid format type!".to_owned())
+        ))
+    );
+    if !crate::is_format_compatible_with_internal_format(internal_format, ET::get_gl_type(), format) {
+        return Err(format!("Data of type {:#x} and format {:#x} can't be uploaded to a texture with internal format {:#x}!", ET::get_gl_type(), format, internal_format));
+    }
+    let epp = unwrap_option_or_ret!(crate::get_elements_per_pixel(ET::get_gl_type(), format), Err("Invalid format type!".to_owned()));
+    let internal_fmt: GLint = unwrap_result_or_ret!(internal_format.try_into(), Err("Converting to gl types!".to_owned()));
+    Ok((internal_fmt, epp))
+}
+
+// NOTE: The multiplication is checked because if it wrapped around the length check could pass with a data slice that is too small and opengl would read past the end of it
+fn validate_data_len<ET>(size: &[usize], cpp: u8, data: &[ET]) -> Result<(), String> {
+    let dims = || size.iter().map(|s| format!("{} pixels", s)).collect::<Vec<String>>().join(" * ");
+    let expected_len = unwrap_option_or_ret!(
+        size.iter().try_fold(usize::from(cpp), |acc, s| acc.checked_mul(*s)),
+        Err(format!("Size provided is: {} * {} values per pixel which is too big!", dims(), cpp))
+    );
+    if expected_len != data.len() {
+        return Err(format!("Size provided is: {} * {} values per pixel = {} =/= {} (size of data array provided)!", dims(), cpp, expected_len, data.len()));
+    }
+    Ok(())
+}
+
+impl PixelStore {
+    /// Checks that the data is big enough for a region of size laid out like this, tightly packed data has to be exactly as big as the region
+    fn validate_data_len<ET>(&self, size: &[usize], epp: u8, data: &[ET]) -> Result<(), String> {
+        if *self == Self::TIGHTLY_PACKED {
+            return validate_data_len(size, epp, data);
+        }
+        let required_len = self.get_required_len(size, epp, std::mem::size_of::<ET>())?;
+        if data.len() < required_len {
+            return Err(format!("A region of size {:?} laid out as {:?} needs {} values but the data array provided only has {}!", size, self, required_len, data.len()));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the size of the data as opengl expects it
+fn validate_compressed_data_len(internal_format: GLenum, size: &[usize], data: &[u8]) -> Result<GLsizei, String> {
+    let expected_len = unwrap_option_or_ret!(
+        crate::get_compressed_image_size(internal_format, size),
+        Err(format!("Size provided is: {:?} which is too big!", size))
+    );
+    if expected_len != data.len() {
+        return Err(format!("An image of size {:?} in compressed format {:#x} is {} bytes =/= {} (size of data array provided)!", size, internal_format, expected_len, data.len()));
+    }
+    Ok(unwrap_result_or_ret!(expected_len.try_into(), Err("Compressed image too big for opengl!".to_owned())))
+}
+
+fn to_gl_size<const N: usize>(size: [usize; N]) -> Result<[GLsizei; N], String> {
+    let mut formatted_siz: [GLsizei; N] = [0; N];
+    for i in 0..N {
+        formatted_siz[i] =
+            unwrap_result_or_ret!(size[i].try_into(), Err(format!("Size[{}] malformed!", i)));
+    }
+    Ok(formatted_siz)
+}
+
+fn to_gl_offset<const N: usize>(offset: [usize; N]) -> Result<[GLint; N], String> {
+    let mut formatted_offset: [GLint; N] = [0; N];
+    for i in 0..N {
+        formatted_offset[i] =
+            unwrap_result_or_ret!(offset[i].try_into(), Err(format!("Offset[{}] malformed!", i)));
+    }
+    Ok(formatted_offset)
+}
+
+/// Returns PACK_ALIGNMENT in bytes, downloads don't support the other pack parameters or pixel pack buffers so those have to be at their defaults
+fn get_pack_alignment() -> Result<usize, String> {
+    let get_integer = |param: GLenum| -> GLint {
+        let mut val: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(param, &mut val);
+        }
+        val
+    };
+    for param in [gl::PACK_ROW_LENGTH, gl::PACK_IMAGE_HEIGHT, gl::PACK_SKIP_PIXELS, gl::PACK_SKIP_ROWS, gl::PACK_SKIP_IMAGES] {
+        if get_integer(param) != 0 {
+            return Err(format!("Pack parameter {:#x} has to be 0 to download a texture!", param));
+        }
+    }
+    if get_integer(gl::PIXEL_PACK_BUFFER_BINDING) != 0 {
+        return Err("A pixel pack buffer is bound, unbind it to download a texture!".to_owned());
+    }
+    let alignment = get_integer(gl::PACK_ALIGNMENT);
+    Ok(unwrap_result_or_ret!(alignment.try_into(), Err("Pack alignment returned from opengl is negative, faulty opengl implementation!".to_owned())))
+}
+
+fn upload_compressed_data_to_target<const N: usize>(
+    target: GLenum,
+    level: usize,
+    internal_format: GLenum,
+    size: [usize; N],
+    data: &[u8],
+) -> Result<(), String> {
+    let image_size = validate_compressed_data_len(internal_format, &size, data)?;
+    let formatted_siz = to_gl_size(size)?;
+    let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+
+    unsafe {
+        internal_gl_compressed_tex_image::<N>(
+            target,
+            level,
+            internal_format,
+            formatted_siz,
+            image_size,
+            data.as_ptr() as *const std::ffi::c_void,
+        );
+    }
+    Ok(())
+}
+
+fn upload_data_to_target<ET, const N: usize>(
+    target: GLenum,
+    level: usize,
+    internal_fmt: GLint,
+    size: [usize; N],
+    data: &[ET],
+    format: GLenum,
+    layout: &PixelStore,
+) -> Result<(), String>
+where
+    ET: HasGLEnum,
+{
+    let epp = unwrap_option_or_ret!(crate::get_elements_per_pixel(ET::get_gl_type(), format), Err("Invalid format type!".to_owned()));
+    layout.validate_data_len(&size, epp, data)?;
+    let formatted_siz = to_gl_size(size)?;
+    let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+
+    layout.with_unpack_state(|| unsafe {
+        internal_gl_tex_image::<N>(
+            target,
+            level,
+            internal_fmt,
+            formatted_siz,
+            0,
+            format,
+            ET::get_gl_type(),
+            data.as_ptr() as *const std::ffi::c_void,
+        );
+    })
+}
+
+// NEEDED BY TextureBuffer, it binds to the same texture units so it has to keep the cached active unit right
+#[inline(always)]
+pub(crate) fn set_active_texture_unit(slot: usize) {
+    if slot != (*texture_binder::LAST_SLOT).load(core::sync::atomic::Ordering::SeqCst){
+            unsafe{
+                gl::ActiveTexture(gl::TEXTURE0 + slot as u32);
+            }
+            (*texture_binder::LAST_SLOT).store(slot, core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+// NEEDED BY TextureUnitAllocator
+#[inline(always)]
+pub(crate) fn get_active_texture_unit() -> usize {
+    (*texture_binder::LAST_SLOT).load(core::sync::atomic::Ordering::SeqCst)
+}
+
 impl<const N: usize, const TYP: GLenum> texture_binder::OnBind for Texture<N, TYP>{
     #[inline(always)]
     fn on_bind<const SLOT: usize>(&self) {
-        if SLOT != (*texture_binder::LAST_SLOT).load(core::sync::atomic::Ordering::SeqCst){
-                unsafe{
-                    gl::ActiveTexture(gl::TEXTURE0 + SLOT as u32);
-                }
-        }
+        crate::render::texture_unit::assert_bouncer_unit_not_reserved(SLOT);
+        set_active_texture_unit(SLOT);
         unsafe{ gl::BindTexture(TYP, self.id); }
 
     }
@@ -67,28 +568,61 @@ impl<const N: usize, const TYP: GLenum> texture_binder::OnBind for Texture<N, TY
 #[one_user(256)]
 pub struct Texture<const N: usize, const TYP: GLenum> {
     id: GLuint,
+    /// Size of mip level 0, all zeroes until it has been allocated
+    size: [usize; N],
+    /// The sized internal format and the format of the data level 0 was allocated with, None until it has been allocated
+    format: Option<(GLint, GLenum)>,
+    /// The number of mip levels allocated with immutable storage, None if the storage is mutable
+    immutable_levels: Option<usize>,
+    /// Which images of every mip level have been allocated, one bit per cube map face ( only the first bit for other textures )
+    allocated_levels: Vec<u8>,
+    /// The bindless handles made from this texture that are still resident
+    resident_handles: ResidentHandles,
+    /// Only here so UnitBindings can tell if the texture has been dropped
+    alive: Arc<()>,
 }
 
 
 impl<const N: usize, const TYP: GLenum> Texture<N, TYP> {
     pub fn new<const BI: usize>(bn: &mut texture_binder::BOUNCER<BI>) -> UnboundTexture<N, TYP> {
-        let mut r = Texture { id: 0 };
+        let mut r = Texture {
+            id: 0,
+            size: [0; N],
+            format: None,
+            immutable_levels: None,
+            allocated_levels: Vec::new(),
+            resident_handles: ResidentHandles::default(),
+            alive: Arc::new(()),
+        };
         unsafe {
             gl::GenTextures(1, &mut r.id);
         }
         let mut r = UnboundTexture::from(r);
         {
-            // Need to set min and mag filter because opengl by default uses mipmaps and we currently do not support that
-            // Not setting the min and mag filters would probablly mean textures would appear as blank
+            // Need to set min and mag filter because opengl by default uses mipmaps and a new texture has no mip levels
+            // Not setting the min and mag filters would probablly mean textures would appear as blank until all mip levels are uploaded or generated
             let mut r = r.bind_mut(bn);
-            r.set_mag_filter_of_bound_tex(gl::LINEAR);
-            r.set_min_filter_of_bound_tex(gl::LINEAR);
+            r.set_mag_filter_of_bound_tex(gl::LINEAR)
+                .expect("Setting the mag filter of a texture without a handle!");
+            r.set_min_filter_of_bound_tex(gl::LINEAR)
+                .expect("Setting a non-mipmapped min filter!");
         }
         r
     }
 
-    #[inline(always)]
-    pub fn set_min_filter_of_bound_tex(&mut self, min_filter: GLuint) {
+    /// NOTE: Mipmapped filters can only be set once all the mip levels between the base and max level are present
+    pub fn set_min_filter_of_bound_tex(&mut self, min_filter: GLuint) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        let is_mipmapped = matches!(
+            min_filter,
+            gl::NEAREST_MIPMAP_NEAREST
+                | gl::LINEAR_MIPMAP_NEAREST
+                | gl::NEAREST_MIPMAP_LINEAR
+                | gl::LINEAR_MIPMAP_LINEAR
+        );
+        if is_mipmapped && !self.is_mip_chain_complete_of_bound_tex() {
+            return Err("Can't use a mipmapped min filter on a texture without a complete mip chain, upload or generate the mip levels first!".to_owned());
+        }
         unsafe {
             gl::TexParameteri(
                 TYP,
@@ -96,10 +630,12 @@ impl<const N: usize, const TYP: GLenum> Texture<N, TYP> {
                 min_filter.try_into().expect("Converting to gl types!"),
             );
         }
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn set_mag_filter_of_bound_tex(&mut self, mag_filter: GLuint) {
+    pub fn set_mag_filter_of_bound_tex(&mut self, mag_filter: GLuint) -> Result<(), String> {
+        self.validate_parameter_change()?;
         unsafe {
             gl::TexParameteri(
                 TYP,
@@ -107,101 +643,1652 @@ impl<const N: usize, const TYP: GLenum> Texture<N, TYP> {
                 mag_filter.try_into().expect("Converting to gl types!"),
             );
         }
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn set_x_wrap_of_bound_tex(&mut self, wrap_x: GLint) {
+    pub fn set_x_wrap_of_bound_tex(&mut self, wrap_x: GLint) -> Result<(), String> {
+        self.validate_parameter_change()?;
         unsafe {
             gl::TexParameteri(TYP, gl::TEXTURE_WRAP_S, wrap_x);
         }
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn set_y_wrap_of_bound_tex(&mut self, wrap_y: GLint) {
+    pub fn set_y_wrap_of_bound_tex(&mut self, wrap_y: GLint) -> Result<(), String> {
+        self.validate_parameter_change()?;
         unsafe {
             gl::TexParameteri(TYP, gl::TEXTURE_WRAP_T, wrap_y);
         }
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn set_z_wrap_of_bound_tex(&mut self, wrap_z: GLint) {
+    pub fn set_z_wrap_of_bound_tex(&mut self, wrap_z: GLint) -> Result<(), String> {
+        self.validate_parameter_change()?;
         unsafe {
             gl::TexParameteri(TYP, gl::TEXTURE_WRAP_R, wrap_z);
         }
+        Ok(())
     }
 
-    pub fn upload_data_to_texture<ET>(
-        &mut self,
-        size: [usize; N],
-        data: &[ET],
-        format: GLenum,
-    ) -> Result<(), String>
-    where
-        ET: HasGLEnum,
-    {
-        let l = data.len();
-        let (internal_fmt, cpp) = unwrap_option_or_ret!(
-            crate::format_to_gl_internal_format(
-                (std::mem::size_of::<ET>() * 8).try_into().unwrap(),
-                format,
-            ),
-            Err("Invalid format type!".to_owned())
-        );
+    #[inline(always)]
+    pub fn set_r_swizzle_of_bound_tex(&mut self, swizzle: Swizzle) -> Result<(), String> {
+        self.set_swizzle_param(gl::TEXTURE_SWIZZLE_R, swizzle)
+    }
 
-        if size[0] * size[1] * usize::from(cpp) != l {
-            return Err(format!("Size provided is: {} pixels * {} pixels * {} values per pixel =/= {} (size of data array provided)!", size[0], size[1], cpp, size[0] * size[1] * usize::from(cpp)));
-        }
-        let mut formatted_siz: [GLsizei; N] = [0; N];
-        for i in 0..N {
-            formatted_siz[i] =
-                unwrap_result_or_ret!(size[i].try_into(), Err(format!("Size[{}] malformed!", i)));
+    #[inline(always)]
+    pub fn set_g_swizzle_of_bound_tex(&mut self, swizzle: Swizzle) -> Result<(), String> {
+        self.set_swizzle_param(gl::TEXTURE_SWIZZLE_G, swizzle)
+    }
+
+    #[inline(always)]
+    pub fn set_b_swizzle_of_bound_tex(&mut self, swizzle: Swizzle) -> Result<(), String> {
+        self.set_swizzle_param(gl::TEXTURE_SWIZZLE_B, swizzle)
+    }
+
+    #[inline(always)]
+    pub fn set_a_swizzle_of_bound_tex(&mut self, swizzle: Swizzle) -> Result<(), String> {
+        self.set_swizzle_param(gl::TEXTURE_SWIZZLE_A, swizzle)
+    }
+
+    /// Sets where the red, green, blue and alpha channels read from in one go
+    /// For example [One, One, One, Red] makes a single channel R8 texture read as white with the red channel as alpha
+    #[inline(always)]
+    pub fn set_swizzle_of_bound_tex(&mut self, swizzle: [Swizzle; 4]) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        let swizzle: [GLint; 4] = swizzle.map(|s| s.get_gl_enum().try_into().expect("Converting to gl types!"));
+        unsafe {
+            gl::TexParameteriv(TYP, gl::TEXTURE_SWIZZLE_RGBA, swizzle.as_ptr());
         }
+        Ok(())
+    }
 
+    #[inline(always)]
+    fn set_swizzle_param(&mut self, param: GLenum, swizzle: Swizzle) -> Result<(), String> {
+        self.validate_parameter_change()?;
         unsafe {
-            internal_gl_tex_image::<N>(
+            gl::TexParameteri(
                 TYP,
-                0,
-                internal_fmt,
-                formatted_siz,
-                0,
-                format,
-                ET::get_gl_type(),
-                &data[0] as *const ET as *const std::ffi::c_void,
+                param,
+                swizzle.get_gl_enum().try_into().expect("Converting to gl types!"),
             );
         }
         Ok(())
     }
 
-    pub fn with_data<ET, const BI: usize>(
-        bn: &mut TextureBouncer<BI>,
-        size: [usize; N],
-        data: &[ET],
-        format: GLenum,
-    ) -> Result<UnboundTexture<N, TYP>, String>
-    where
-        ET: HasGLEnum,
-    {
-        let mut r = Self::new(bn);
-        {
-            let mut r = r.bind_mut(bn);
-            r.upload_data_to_texture(size, data, format)?;
+    /// COMPARE_REF_TO_TEXTURE makes shadow samplers compare the reference value against the texture using the compare function, NONE disables comparison
+    /// NOTE: Comparison only works on textures with a depth format
+    pub fn set_compare_mode_of_bound_tex(&mut self, compare_mode: GLenum) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        match compare_mode {
+            gl::NONE => {}
+            gl::COMPARE_REF_TO_TEXTURE => {
+                if !self.has_depth_format() {
+                    return Err("Only textures with a depth format can be compared against!".to_owned());
+                }
+            }
+            _ => return Err(format!("Invalid compare mode: {:#x}!", compare_mode)),
         }
-        Ok(r)
+        unsafe {
+            gl::TexParameteri(
+                TYP,
+                gl::TEXTURE_COMPARE_MODE,
+                compare_mode.try_into().expect("Converting to gl types!"),
+            );
+        }
+        Ok(())
     }
-}
 
-impl<const N: usize, const TYP: GLenum> Drop for Texture<N, TYP> {
-    fn drop(&mut self) {
+    pub fn set_compare_func_of_bound_tex(&mut self, compare_func: GLenum) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        if !matches!(
+            compare_func,
+            gl::LEQUAL
+                | gl::GEQUAL
+                | gl::LESS
+                | gl::GREATER
+                | gl::EQUAL
+                | gl::NOTEQUAL
+                | gl::ALWAYS
+                | gl::NEVER
+        ) {
+            return Err(format!("Invalid compare function: {:#x}!", compare_func));
+        }
         unsafe {
-            gl::DeleteTextures(1, &self.id);
+            gl::TexParameteri(
+                TYP,
+                gl::TEXTURE_COMPARE_FUNC,
+                compare_func.try_into().expect("Converting to gl types!"),
+            );
         }
+        Ok(())
     }
-}
 
-}
+    /// Selects whether shaders read the depth ( DEPTH_COMPONENT ) or the stencil ( STENCIL_INDEX ) part of a combined depth and stencil texture
+    /// NOTE: Stencil values have to be read with an unsigned integer sampler
+    pub fn set_depth_stencil_mode_of_bound_tex(&mut self, mode: GLenum) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        if !matches!(mode, gl::DEPTH_COMPONENT | gl::STENCIL_INDEX) {
+            return Err(format!("Invalid depth stencil mode: {:#x}!", mode));
+        }
+        if self.get_format() != Some(gl::DEPTH_STENCIL) {
+            return Err("Only textures with a combined depth and stencil format have a depth stencil mode!".to_owned());
+        }
+        unsafe {
+            gl::TexParameteri(
+                TYP,
+                gl::DEPTH_STENCIL_TEXTURE_MODE,
+                mode.try_into().expect("Converting to gl types!"),
+            );
+        }
+        Ok(())
+    }
+
+    /// 1.0 means no anisotropic filtering, anything else has to be at most crate::get_max_anisotropy()
+    pub fn set_max_anisotropy_of_bound_tex(&mut self, max_anisotropy: f32) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        crate::validate_max_anisotropy(max_anisotropy)?;
+        // NOTE: Setting 1.0 would be an invalid enum on implementations without anisotropic filtering and it's already the default there
+        if max_anisotropy != 1.0 || crate::get_max_anisotropy().is_some() {
+            unsafe {
+                gl::TexParameterf(TYP, crate::GL_TEXTURE_MAX_ANISOTROPY, max_anisotropy);
+            }
+        }
+        Ok(())
+    }
+
+    /// The color used when sampling outside the texture with the CLAMP_TO_BORDER wrap mode
+    #[inline(always)]
+    pub fn set_border_color_of_bound_tex(&mut self, color: [f32; 4]) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        unsafe {
+            gl::TexParameterfv(TYP, gl::TEXTURE_BORDER_COLOR, color.as_ptr());
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_base_level_of_bound_tex(&mut self, level: usize) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        unsafe {
+            gl::TexParameteri(TYP, gl::TEXTURE_BASE_LEVEL, level);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_max_level_of_bound_tex(&mut self, level: usize) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        unsafe {
+            gl::TexParameteri(TYP, gl::TEXTURE_MAX_LEVEL, level);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_lod_bias_of_bound_tex(&mut self, bias: f32) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        unsafe {
+            gl::TexParameterf(TYP, gl::TEXTURE_LOD_BIAS, bias);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_min_lod_of_bound_tex(&mut self, min_lod: f32) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        unsafe {
+            gl::TexParameterf(TYP, gl::TEXTURE_MIN_LOD, min_lod);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_max_lod_of_bound_tex(&mut self, max_lod: f32) -> Result<(), String> {
+        self.validate_parameter_change()?;
+        unsafe {
+            gl::TexParameterf(TYP, gl::TEXTURE_MAX_LOD, max_lod);
+        }
+        Ok(())
+    }
+
+    /// Generates all mip levels after the base level from the base level, the base level must have already been uploaded
+    pub fn generate_mipmaps_of_bound_tex(&mut self) -> Result<(), String> {
+        // NOTE: Generating mipmaps only writes to the levels of immutable textures, but it respecifies them on mutable ones
+        self.validate_bound_to_active_unit()?;
+        if self.immutable_levels.is_none() {
+            self.resident_handles.validate_no_handle()?;
+        }
+        let base_level = Self::get_tex_parameter(gl::TEXTURE_BASE_LEVEL)?;
+        let max_level = Self::get_tex_parameter(gl::TEXTURE_MAX_LEVEL)?;
+        for target in Self::get_image_targets() {
+            if Self::get_size_of_level(*target, base_level)?.contains(&0) {
+                return Err(format!("Can't generate mipmaps, base level {} has not been uploaded!", base_level));
+            }
+        }
+        unsafe {
+            gl::GenerateMipmap(TYP);
+        }
+        // Every level after the base level up to the max level ( or the last level that can exist ) is allocated now
+        let last_level = max_level.min(Self::get_num_of_mip_levels(self.size).saturating_sub(1));
+        for level in base_level + 1..=last_level {
+            self.record_image(level, Self::get_all_images_mask());
+        }
+        Ok(())
+    }
+
+    /// Checks that every mip level from the base level up to the max level ( or the last level that can exist ) is present and of the right size
+    /// NOTE: This queries opengl so it is not as lightweight as other getters
+    pub fn is_mip_chain_complete_of_bound_tex(&self) -> bool {
+        if self.validate_bound_to_active_unit().is_err() {
+            return false;
+        }
+        let mut base_level = unwrap_result_or_ret!(Self::get_tex_parameter(gl::TEXTURE_BASE_LEVEL), false);
+        let mut max_level = unwrap_result_or_ret!(Self::get_tex_parameter(gl::TEXTURE_MAX_LEVEL), false);
+        // Immutable textures only ever sample the levels they were allocated with, whatever the base and max level are
+        // NOTE: Opengl clamps the base level to the last level and the max level to between the base level and the last level
+        if let Some(levels) = self.immutable_levels {
+            let last_level = levels.saturating_sub(1);
+            base_level = base_level.min(last_level);
+            max_level = max_level.clamp(base_level, last_level);
+        }
+        for target in Self::get_image_targets() {
+            let base = unwrap_result_or_ret!(Self::get_size_of_level(*target, base_level), false);
+            let last_level = max_level.min(base_level + Self::get_num_of_mip_levels(base).saturating_sub(1));
+            if base.contains(&0) || last_level < base_level {
+                return false;
+            }
+            for level in base_level + 1..=last_level {
+                let size = unwrap_result_or_ret!(Self::get_size_of_level(*target, level), false);
+                if size != Self::get_size_of_mip_level(base, level - base_level) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Cube maps have one image per face for every mip level, other textures just have one
+    #[inline(always)]
+    fn get_image_targets() -> &'static [GLenum] {
+        const FACE_TARGETS: [GLenum; 6] = [
+            gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+            gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+            gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+            gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+            gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        ];
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            &FACE_TARGETS
+        } else {
+            std::slice::from_ref(&TYP)
+        }
+    }
+
+    fn get_tex_parameter(param: GLenum) -> Result<usize, String> {
+        let mut val: GLint = 0;
+        unsafe {
+            gl::GetTexParameteriv(TYP, param, &mut val);
+        }
+        Ok(unwrap_result_or_ret!(val.try_into(), Err("Texture parameter returned from opengl is negative, faulty opengl implementation!".to_owned())))
+    }
+
+    /// Returns all zeroes if the level has not been uploaded
+    fn get_size_of_level(target: GLenum, level: usize) -> Result<[usize; N], String> {
+        const QUERIES: [GLenum; 3] = [gl::TEXTURE_WIDTH, gl::TEXTURE_HEIGHT, gl::TEXTURE_DEPTH];
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        let mut size = [0; N];
+        for i in 0..N {
+            let mut val: GLint = 0;
+            unsafe {
+                gl::GetTexLevelParameteriv(target, level, QUERIES[i], &mut val);
+            }
+            size[i] = unwrap_result_or_ret!(val.try_into(), Err("Size returned from opengl is negative, faulty opengl implementation!".to_owned()));
+        }
+        Ok(size)
+    }
+
+    /// The layer dimension of array textures does not shrink between mip levels
+    #[inline(always)]
+    fn is_layer_dimension(i: usize) -> bool {
+        match TYP {
+            gl::TEXTURE_1D_ARRAY => i == 1,
+            gl::TEXTURE_2D_ARRAY | gl::TEXTURE_CUBE_MAP_ARRAY => i == 2,
+            _ => false,
+        }
+    }
+
+    fn get_size_of_mip_level(base: [usize; N], level: usize) -> [usize; N] {
+        let mut size = base;
+        for (i, s) in size.iter_mut().enumerate() {
+            if !Self::is_layer_dimension(i) {
+                *s = u32::try_from(level).ok().and_then(|l| s.checked_shr(l)).unwrap_or(0).max(1);
+            }
+        }
+        size
+    }
+
+    fn get_num_of_mip_levels(base: [usize; N]) -> usize {
+        let biggest = (0..N).filter(|i| !Self::is_layer_dimension(*i)).map(|i| base[i]).max().unwrap_or(0);
+        if biggest == 0 {
+            0
+        } else {
+            (usize::BITS - biggest.leading_zeros()) as usize
+        }
+    }
+
+    /// Level 0 defines the size and format of the texture, every other level must have exactly the size opengl expects given the size of level 0 and the same format
+    fn validate_level(&self, level: usize, size: [usize; N], internal_fmt: GLint) -> Result<(), String> {
+        if level == 0 {
+            return Ok(());
+        }
+        let (tex_internal_fmt, _) = unwrap_option_or_ret!(self.format, Err("Mip level 0 has to be uploaded before any other mip level!".to_owned()));
+        if internal_fmt != tex_internal_fmt {
+            return Err(format!("Mip level {} must have the same internal format as mip level 0 ({:#x}), not {:#x}!", level, tex_internal_fmt, internal_fmt));
+        }
+        if level >= Self::get_num_of_mip_levels(self.size) {
+            return Err(format!("Mip level {} does not exist for a texture whose level 0 is of size {:?}!", level, self.size));
+        }
+        let expected = Self::get_size_of_mip_level(self.size, level);
+        if size != expected {
+            return Err(format!("Mip level {} of a texture whose level 0 is of size {:?} must be of size {:?}, not {:?}!", level, self.size, expected, size));
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_mutable(&self) -> Result<(), String> {
+        if self.immutable_levels.is_some() {
+            return Err("Texture has immutable storage, it can only be updated with update_region!".to_owned());
+        }
+        self.resident_handles.validate_no_handle()?;
+        self.validate_bound_to_active_unit()
+    }
+
+    /// Parameters are set on whatever is bound to the active unit and can't be changed at all once a bindless handle has been made
+    #[inline(always)]
+    fn validate_parameter_change(&self) -> Result<(), String> {
+        self.validate_bound_to_active_unit()?;
+        self.resident_handles.validate_no_handle()
+    }
+
+    /// Records that the image of target ( TYP or a cube map face ) at level has been allocated
+    fn record_level(&mut self, target: GLenum, level: usize, size: [usize; N], internal_fmt: GLint, format: GLenum) {
+        if level == 0 {
+            // NOTE: Respecifying level 0 with another size or internal format leaves the other levels with the wrong size ( or format ), so they have to be uploaded again
+            if size != self.size || self.get_internal_format() != Some(internal_fmt) {
+                self.allocated_levels.clear();
+            }
+            self.size = size;
+            self.format = Some((internal_fmt, format));
+        }
+        let image = if target == TYP { 1 } else { 1 << (target - gl::TEXTURE_CUBE_MAP_POSITIVE_X) };
+        self.record_image(level, image);
+    }
+
+    #[inline(always)]
+    fn record_image(&mut self, level: usize, images: u8) {
+        if self.allocated_levels.len() <= level {
+            self.allocated_levels.resize(level + 1, 0);
+        }
+        self.allocated_levels[level] |= images;
+    }
+
+    /// The bits allocated_levels has for a level once all of its images have been allocated
+    #[inline(always)]
+    fn get_all_images_mask() -> u8 {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            0x3F
+        } else {
+            1
+        }
+    }
+
+    /// Size of mip level 0, all zeroes if nothing has been allocated yet
+    #[inline(always)]
+    pub fn get_size(&self) -> [usize; N] {
+        self.size
+    }
+
+    /// The sized internal format of the texture, None if nothing has been allocated yet
+    #[inline(always)]
+    pub fn get_internal_format(&self) -> Option<GLint> {
+        self.format.map(|(internal_fmt, _)| internal_fmt)
+    }
+
+    /// The format of the data the texture was allocated with and which update_region expects, None if nothing has been allocated yet
+    /// NOTE: For compressed textures this is the compressed internal format, which is what update_compressed_region expects
+    #[inline(always)]
+    pub fn get_format(&self) -> Option<GLenum> {
+        self.format.map(|(_, format)| format)
+    }
+
+    /// The number of mip levels the texture can have given the size of level 0 ( or that were allocated for immutable textures ), 0 if nothing has been allocated yet
+    /// NOTE: For mutable textures this doesn't mean they have all been allocated, see is_level_allocated
+    #[inline(always)]
+    pub fn get_num_of_levels(&self) -> usize {
+        self.immutable_levels
+            .unwrap_or_else(|| Self::get_num_of_mip_levels(self.size))
+    }
+
+    /// True once the mip level has been uploaded, allocated or generated ( for cube maps, every face of it )
+    #[inline(always)]
+    pub fn is_level_allocated(&self, level: usize) -> bool {
+        self.allocated_levels.get(level) == Some(&Self::get_all_images_mask())
+    }
+
+    /// The size of a mip level, None if the level has not been allocated
+    #[inline(always)]
+    pub fn get_level_size(&self, level: usize) -> Option<[usize; N]> {
+        if !self.is_level_allocated(level) {
+            return None;
+        }
+        Some(Self::get_size_of_mip_level(self.size, level))
+    }
+
+    /// True if the texture has a depth or a combined depth and stencil internal format
+    #[inline(always)]
+    pub fn has_depth_format(&self) -> bool {
+        self.get_internal_format()
+            .and_then(|internal_fmt| GLenum::try_from(internal_fmt).ok())
+            .is_some_and(crate::is_depth_internal_format)
+    }
+
+    // NEEDED BY Framebuffer and TextureImage
+    #[inline(always)]
+    pub(crate) fn get_id(&self) -> GLuint {
+        self.id
+    }
+
+    /// What a TextureUnitAllocator needs to bind the texture to a unit chosen at runtime
+    #[inline(always)]
+    pub fn get_unit_binding(&self) -> UnitBinding {
+        UnitBinding::new(TYP, self.id, &self.alive)
+    }
+
+    /// Gets the bindless handle of the texture and makes it resident so shaders can sample it without binding it to a texture unit
+    /// NOTE: Needs ARB_bindless_texture, once a texture has a handle its parameters and storage can't be changed anymore ( the setters and uploads return an error ), only the contents of its levels can be updated
+    pub fn make_handle_resident(&self) -> Result<ResidentTextureHandle, String> {
+        self.validate_bindless()?;
+        let handle = unsafe { crate::gl_ext::GetTextureHandleARB(self.id) };
+        bindless::make_resident(handle, &[&self.resident_handles])
+    }
+
+    /// Same as make_handle_resident but the handle samples the texture with the parameters of the sampler instead of its own
+    pub fn make_handle_resident_with_sampler(&self, sampler: &Sampler) -> Result<ResidentTextureHandle, String> {
+        self.validate_bindless()?;
+        let handle = unsafe { crate::gl_ext::GetTextureSamplerHandleARB(self.id, sampler.get_id()) };
+        bindless::make_resident(handle, &[&self.resident_handles, sampler.get_resident_handles()])
+    }
+
+    fn validate_bindless(&self) -> Result<(), String> {
+        if !bindless::is_bindless_texture_supported() {
+            return Err("Bindless textures are not supported!".to_owned());
+        }
+        if self.format.is_none() {
+            return Err("Texture has to be allocated before it can have a handle!".to_owned());
+        }
+        Ok(())
+    }
+
+    /// The number of mip levels allocated if the texture has immutable storage, None otherwise
+    #[inline(always)]
+    pub fn get_immutable_levels(&self) -> Option<usize> {
+        self.immutable_levels
+    }
+
+    fn validate_size_for_target(size: [usize; N]) -> Result<(), String> {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            return Err("Cube maps have to be uploaded one face at a time, use upload_data_to_cube_faces instead!".to_owned());
+        }
+        // Cube map arrays are uploaded as layer-faces, so the depth is 6 * the number of cubes
+        if TYP == gl::TEXTURE_CUBE_MAP_ARRAY && (size[0] != size[1] || !size[2].is_multiple_of(6)) {
+            return Err(format!("Cube map array faces must be square and the depth a multiple of 6, got {} pixels * {} pixels * {} layer-faces!", size[0], size[1], size[2]));
+        }
+        Ok(())
+    }
+
+    fn validate_internal_format_for_target(internal_fmt: GLint) -> Result<(), String> {
+        let is_depth = GLenum::try_from(internal_fmt).is_ok_and(crate::is_depth_internal_format);
+        if is_depth && TYP == gl::TEXTURE_3D {
+            return Err("3D textures can't have a depth or stencil format!".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Returns the internal format as the GLint opengl expects when creating a texture
+    fn validate_compressed_internal_format(internal_format: GLenum) -> Result<GLint, String> {
+        if !crate::is_compressed_internal_format(internal_format) {
+            return Err(format!("Unsupported compressed internal format: {:#x}!", internal_format));
+        }
+        // NOTE: All the supported formats are made of 2D blocks, which opengl only allows for images made of 2D layers
+        if !matches!(TYP, gl::TEXTURE_2D | gl::TEXTURE_2D_ARRAY | gl::TEXTURE_CUBE_MAP | gl::TEXTURE_CUBE_MAP_ARRAY) {
+            return Err("Compressed formats can only be used with 2D, 2D array, cube map and cube map array textures!".to_owned());
+        }
+        Ok(unwrap_result_or_ret!(internal_format.try_into(), Err("Converting to gl types!".to_owned())))
+    }
+
+    /// True if the texture has a block compressed internal format
+    #[inline(always)]
+    pub fn is_compressed(&self) -> bool {
+        self.get_internal_format()
+            .and_then(|internal_fmt| GLenum::try_from(internal_fmt).ok())
+            .is_some_and(crate::is_compressed_internal_format)
+    }
+
+    /// Level 0 uses the default internal format for the data, other levels use the internal format of level 0
+    #[inline(always)]
+    fn get_internal_format_for_level(&self, level: usize, internal_format: Option<GLenum>) -> Option<GLenum> {
+        if level == 0 {
+            return internal_format;
+        }
+        internal_format.or_else(|| self.get_internal_format().and_then(|internal_fmt| internal_fmt.try_into().ok()))
+    }
+
+    /// Level 0 can be of any size, other levels have to be exactly as big as opengl expects them to be given the size of level 0
+    /// Level 0 is stored in the default internal format for the type and format of the data, other levels in the same internal format as level 0
+    #[inline(always)]
+    pub fn upload_data_to_texture<ET>(
+        &mut self,
+        level: usize,
+        size: [usize; N],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_data(level, None, size, data, format, &PixelStore::TIGHTLY_PACKED)
+    }
+
+    /// Same as upload_data_to_texture but the data is laid out as described by layout, for example to upload a region of a bigger image
+    #[inline(always)]
+    pub fn upload_data_to_texture_with_layout<ET>(
+        &mut self,
+        level: usize,
+        size: [usize; N],
+        data: &[ET],
+        format: GLenum,
+        layout: &PixelStore,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_data(level, None, size, data, format, layout)
+    }
+
+    /// Same as upload_data_to_texture but the data is converted to and stored as internal_format ( for example SRGB8_ALPHA8 or RGBA16F )
+    #[inline(always)]
+    pub fn upload_data_to_texture_with_internal_format<ET>(
+        &mut self,
+        level: usize,
+        internal_format: GLenum,
+        size: [usize; N],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_data(level, Some(internal_format), size, data, format, &PixelStore::TIGHTLY_PACKED)
+    }
+
+    fn upload_data<ET>(
+        &mut self,
+        level: usize,
+        internal_format: Option<GLenum>,
+        size: [usize; N],
+        data: &[ET],
+        format: GLenum,
+        layout: &PixelStore,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.validate_mutable()?;
+        Self::validate_size_for_target(size)?;
+        let (internal_fmt, _) = get_gl_format_of::<ET>(self.get_internal_format_for_level(level, internal_format), format)?;
+        Self::validate_internal_format_for_target(internal_fmt)?;
+        self.validate_level(level, size, internal_fmt)?;
+        upload_data_to_target(TYP, level, internal_fmt, size, data, format, layout)?;
+        self.record_level(TYP, level, size, internal_fmt, format);
+        Ok(())
+    }
+
+    /// Allocates the storage of the texture without uploading anything to it, the contents are undefined until they are uploaded
+    #[inline(always)]
+    pub fn allocate_texture_data<ET>(&mut self, size: [usize; N], format: GLenum) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.allocate_data::<ET>(None, size, format)
+    }
+
+    /// Same as allocate_texture_data but the texture is stored as internal_format
+    #[inline(always)]
+    pub fn allocate_texture_data_with_internal_format<ET>(
+        &mut self,
+        internal_format: GLenum,
+        size: [usize; N],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.allocate_data::<ET>(Some(internal_format), size, format)
+    }
+
+    fn allocate_data<ET>(&mut self, internal_format: Option<GLenum>, size: [usize; N], format: GLenum) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.validate_mutable()?;
+        Self::validate_size_for_target(size)?;
+        let (internal_fmt, _) = get_gl_format_of::<ET>(internal_format, format)?;
+        Self::validate_internal_format_for_target(internal_fmt)?;
+        let formatted_siz = to_gl_size(size)?;
+        unsafe {
+            internal_gl_tex_image::<N>(
+                TYP,
+                0,
+                internal_fmt,
+                formatted_siz,
+                0,
+                format,
+                ET::get_gl_type(),
+                std::ptr::null(),
+            );
+        }
+        self.record_level(TYP, 0, size, internal_fmt, format);
+        Ok(())
+    }
+
+    /// Allocates all the mip levels of the texture at once with a fixed internal format, after this the size and format of the texture can't change
+    /// and it can only be updated with update_region, the contents are undefined until they are uploaded
+    /// NOTE: For cube maps the size is the size of one face
+    pub fn allocate_immutable_storage(
+        &mut self,
+        levels: usize,
+        internal_format: GLenum,
+        size: [usize; N],
+    ) -> Result<(), String> {
+        self.validate_mutable()?;
+        if TYP == gl::TEXTURE_CUBE_MAP && size[0] != size[1] {
+            return Err(format!("Cube map faces must be square, got {} pixels * {} pixels!", size[0], size[1]));
+        } else if TYP != gl::TEXTURE_CUBE_MAP {
+            Self::validate_size_for_target(size)?;
+        }
+        let max_levels = Self::get_num_of_mip_levels(size);
+        if levels == 0 || levels > max_levels {
+            return Err(format!("A texture of size {:?} can have between 1 and {} mip levels, not {}!", size, max_levels, levels));
+        }
+        let (internal_fmt, format) = if crate::is_compressed_internal_format(internal_format) {
+            (Self::validate_compressed_internal_format(internal_format)?, internal_format)
+        } else {
+            let (_, format) = unwrap_option_or_ret!(
+                crate::gl_internal_format_to_format(internal_format),
+                Err(format!("Unsupported internal format: {:#x}!", internal_format))
+            );
+            let internal_fmt: GLint = unwrap_result_or_ret!(internal_format.try_into(), Err("Converting to gl types!".to_owned()));
+            (internal_fmt, format)
+        };
+        Self::validate_internal_format_for_target(internal_fmt)?;
+        let formatted_siz = to_gl_size(size)?;
+        let gl_levels: GLsizei = unwrap_result_or_ret!(levels.try_into(), Err("Too many mip levels for opengl!".to_owned()));
+        unsafe {
+            internal_gl_tex_storage::<N>(TYP, gl_levels, internal_format, formatted_siz);
+        }
+        self.record_level(TYP, 0, size, internal_fmt, format);
+        self.immutable_levels = Some(levels);
+        self.allocated_levels = vec![Self::get_all_images_mask(); levels];
+        Ok(())
+    }
+
+    pub fn with_immutable_storage<const BI: usize>(
+        bn: &mut TextureBouncer<BI>,
+        levels: usize,
+        internal_format: GLenum,
+        size: [usize; N],
+    ) -> Result<UnboundTexture<N, TYP>, String> {
+        let mut r = Self::new(bn);
+        {
+            let mut r = r.bind_mut(bn);
+            r.allocate_immutable_storage(levels, internal_format, size)?;
+        }
+        Ok(r)
+    }
+
+    /// The number of dimensions of a texture of the target that aren't layers
+    #[inline(always)]
+    fn get_num_of_spatial_dims(target: GLenum) -> usize {
+        match target {
+            gl::TEXTURE_1D | gl::TEXTURE_1D_ARRAY => 1,
+            gl::TEXTURE_3D => 3,
+            _ => 2,
+        }
+    }
+
+    #[inline(always)]
+    fn is_array_target(target: GLenum) -> bool {
+        matches!(target, gl::TEXTURE_1D_ARRAY | gl::TEXTURE_2D_ARRAY | gl::TEXTURE_CUBE_MAP_ARRAY)
+    }
+
+    /// Creates a texture that shares the storage of this one but reads it as another compatible internal format ( see crate::are_internal_formats_view_compatible ), target or range of levels and layers
+    /// Layers are the layers of array textures and the faces ( or layer-faces ) of cube maps, textures without layers have only layer 0
+    /// A 2D texture can be viewed as a 2D array and the other way around, cube maps ( and arrays of them ) as 2D textures, 2D arrays, cube maps or cube map arrays and 1D textures as 1D arrays and the other way around
+    /// NOTE: Needs opengl 4.3 ( or ARB_texture_view ) and the texture has to have immutable storage, bn has to be a different bouncer than the one this texture is bound with
+    pub fn create_view<const VN: usize, const VTYP: GLenum, const BI: usize>(
+        &self,
+        bn: &mut TextureBouncer<BI>,
+        internal_format: GLenum,
+        levels: Range<usize>,
+        layers: Range<usize>,
+    ) -> Result<UnboundTexture<VN, VTYP>, String> {
+        if crate::get_gl_version() < (4, 3) && !crate::is_gl_extension_supported("GL_ARB_texture_view") {
+            return Err("Texture views are not supported!".to_owned());
+        }
+        let immutable_levels = unwrap_option_or_ret!(self.immutable_levels, Err("Only textures with immutable storage can be viewed!".to_owned()));
+        let (tex_internal_fmt, _) = unwrap_option_or_ret!(self.format, Err("Texture has to be allocated before it can be viewed!".to_owned()));
+        let tex_internal_fmt: GLenum = unwrap_result_or_ret!(tex_internal_fmt.try_into(), Err("Internal format of texture malformed!".to_owned()));
+
+        let can_view = match TYP {
+            gl::TEXTURE_1D | gl::TEXTURE_1D_ARRAY => matches!(VTYP, gl::TEXTURE_1D | gl::TEXTURE_1D_ARRAY),
+            gl::TEXTURE_2D | gl::TEXTURE_2D_ARRAY => matches!(VTYP, gl::TEXTURE_2D | gl::TEXTURE_2D_ARRAY),
+            gl::TEXTURE_3D => VTYP == gl::TEXTURE_3D,
+            gl::TEXTURE_CUBE_MAP | gl::TEXTURE_CUBE_MAP_ARRAY => matches!(VTYP, gl::TEXTURE_2D | gl::TEXTURE_2D_ARRAY | gl::TEXTURE_CUBE_MAP | gl::TEXTURE_CUBE_MAP_ARRAY),
+            _ => false,
+        };
+        let spatial_dims = Self::get_num_of_spatial_dims(VTYP);
+        if !can_view || VN != spatial_dims + usize::from(Self::is_array_target(VTYP)) {
+            return Err(format!("Textures of target {:#x} can't be viewed as textures of target {:#x} with {} dimensions!", TYP, VTYP, VN));
+        }
+        if !crate::are_internal_formats_view_compatible(tex_internal_fmt, internal_format) {
+            return Err(format!("Textures with internal format {:#x} can't be viewed as internal format {:#x}!", tex_internal_fmt, internal_format));
+        }
+        let format = if crate::is_compressed_internal_format(internal_format) {
+            internal_format
+        } else {
+            let (_, format) = unwrap_option_or_ret!(
+                crate::gl_internal_format_to_format(internal_format),
+                Err(format!("Unsupported internal format: {:#x}!", internal_format))
+            );
+            format
+        };
+
+        if levels.is_empty() || levels.end > immutable_levels {
+            return Err(format!("Levels {:?} are not inside the {} mip levels of the texture!", levels, immutable_levels));
+        }
+        let size = Self::get_size_of_mip_level(self.size, levels.start);
+        let tex_layers = match TYP {
+            gl::TEXTURE_CUBE_MAP => CubeFace::ALL.len(),
+            _ if Self::is_array_target(TYP) => size[N - 1],
+            _ => 1,
+        };
+        if layers.is_empty() || layers.end > tex_layers {
+            return Err(format!("Layers {:?} are not inside the {} layers of the texture!", layers, tex_layers));
+        }
+        let num_of_layers = layers.len();
+        let layers_fit = match VTYP {
+            gl::TEXTURE_CUBE_MAP => num_of_layers == 6,
+            gl::TEXTURE_CUBE_MAP_ARRAY => num_of_layers.is_multiple_of(6),
+            _ => Self::is_array_target(VTYP) || num_of_layers == 1,
+        };
+        if !layers_fit {
+            return Err(format!("A view of target {:#x} can't have {} layers!", VTYP, num_of_layers));
+        }
+
+        let mut view_size = [0; VN];
+        view_size[..spatial_dims].copy_from_slice(&size[..spatial_dims]);
+        if Self::is_array_target(VTYP) {
+            view_size[spatial_dims] = num_of_layers;
+        }
+        let view_internal_fmt: GLint = unwrap_result_or_ret!(internal_format.try_into(), Err("Converting to gl types!".to_owned()));
+        let mut r = Texture::<VN, VTYP> {
+            id: 0,
+            size: view_size,
+            format: Some((view_internal_fmt, format)),
+            immutable_levels: Some(levels.len()),
+            allocated_levels: vec![Texture::<VN, VTYP>::get_all_images_mask(); levels.len()],
+            resident_handles: ResidentHandles::default(),
+            alive: Arc::new(()),
+        };
+        unsafe {
+            gl::GenTextures(1, &mut r.id);
+            // NOTE: The view has to be created before the name is ever bound
+            gl::TextureView(
+                r.id,
+                VTYP,
+                self.id,
+                internal_format,
+                levels.start.try_into().expect("Converting to gl types!"),
+                levels.len().try_into().expect("Converting to gl types!"),
+                layers.start.try_into().expect("Converting to gl types!"),
+                num_of_layers.try_into().expect("Converting to gl types!"),
+            );
+        }
+        let mut r = UnboundTexture::from(r);
+        {
+            // Same as in new, the default min filter would need mip levels the view might not have
+            let mut r = r.bind_mut(bn);
+            r.set_mag_filter_of_bound_tex(gl::LINEAR)
+                .expect("Setting the mag filter of a texture without a handle!");
+            r.set_min_filter_of_bound_tex(gl::LINEAR)
+                .expect("Setting a non-mipmapped min filter!");
+        }
+        Ok(r)
+    }
+
+    /// Updates part of mip level 0 without reallocating the texture, see update_region_of_level
+    #[inline(always)]
+    pub fn update_region<ET>(&mut self, offset: [usize; N], size: [usize; N], data: &[ET]) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.update_region_of_level(0, offset, size, data)
+    }
+
+    /// The data is expected in the format the texture was allocated with and the region has to fit inside the mip level
+    pub fn update_region_of_level<ET>(
+        &mut self,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        data: &[ET],
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.update_region_of_level_with_layout(level, offset, size, data, &PixelStore::TIGHTLY_PACKED)
+    }
+
+    /// Same as update_region_of_level but the data is laid out as described by layout, for example to update from a region of a bigger image
+    pub fn update_region_of_level_with_layout<ET>(
+        &mut self,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        data: &[ET],
+        layout: &PixelStore,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            return Err("Cube maps have to be updated one face at a time, use update_cube_face_region instead!".to_owned());
+        }
+        self.update_region_of_target(TYP, level, offset, size, data, None, layout)
+    }
+
+    /// Same as update_region_of_level but the data is laid out as format ( like gl::BGRA ) instead of the format the texture was allocated with
+    pub fn update_region_of_level_with_format<ET>(
+        &mut self,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            return Err("Cube maps have to be updated one face at a time, use update_cube_face_region_with_format instead!".to_owned());
+        }
+        self.update_region_of_target(TYP, level, offset, size, data, Some(format), &PixelStore::TIGHTLY_PACKED)
+    }
+
+    /// data_format is the format of the data, None if it's the format the texture was allocated with
+    #[allow(clippy::too_many_arguments)]
+    fn update_region_of_target<ET>(
+        &mut self,
+        target: GLenum,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        data: &[ET],
+        data_format: Option<GLenum>,
+        layout: &PixelStore,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        let (format, epp) = self.validate_region_update::<ET>(level, offset, size, data_format)?;
+        layout.validate_data_len(&size, epp, data)?;
+
+        let formatted_offset = to_gl_offset(offset)?;
+        let formatted_siz = to_gl_size(size)?;
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        layout.with_unpack_state(|| unsafe {
+            internal_gl_tex_sub_image::<N>(
+                target,
+                level,
+                formatted_offset,
+                formatted_siz,
+                format,
+                ET::get_gl_type(),
+                data.as_ptr() as *const std::ffi::c_void,
+            );
+        })
+    }
+
+    /// Checks that data of ET laid out as data_format ( or the format the texture was allocated with if None ) can be uploaded to the region of the mip level
+    /// Returns the format of the data and the number of elements per pixel
+    fn validate_region_update<ET>(&self, level: usize, offset: [usize; N], size: [usize; N], data_format: Option<GLenum>) -> Result<(GLenum, u8), String>
+    where
+        ET: HasGLEnum,
+    {
+        let (tex_internal_fmt, tex_format) = unwrap_option_or_ret!(self.format, Err("Texture has to be allocated before a region of it can be updated!".to_owned()));
+        if self.is_compressed() {
+            return Err("Texture has a compressed format, use update_compressed_region instead!".to_owned());
+        }
+        let tex_internal_fmt: GLenum = unwrap_result_or_ret!(tex_internal_fmt.try_into(), Err("Converting to gl types!".to_owned()));
+        let format = data_format.unwrap_or(tex_format);
+        let (_, epp) = get_gl_format_of::<ET>(Some(tex_internal_fmt), format)?;
+        self.validate_region(level, offset, size)?;
+        self.validate_bound_to_active_unit()?;
+        Ok((format, epp))
+    }
+
+    // NEEDED BY PixelUnpackRing
+    /// Checks that update_region_from_unpack_buffer can update the region of the mip level, without touching any buffers
+    /// Returns the format of the data and the number of elements per pixel
+    pub(crate) fn validate_unpack_buffer_update<ET>(&self, level: usize, offset: [usize; N], size: [usize; N]) -> Result<(GLenum, u8), String>
+    where
+        ET: HasGLEnum,
+    {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            return Err("Cube maps have to be updated one face at a time!".to_owned());
+        }
+        self.validate_region_update::<ET>(level, offset, size, None)
+    }
+
+    // NEEDED BY PixelUnpackRing
+    /// Same as update_region_of_level but the tightly packed data is read from the pixel unpack buffer that is bound, starting buffer_offset elements into it
+    /// buffer_len is the number of elements in the buffer after buffer_offset, so opengl never reads past its end
+    pub(crate) fn update_region_from_unpack_buffer<ET>(
+        &mut self,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        buffer_offset: usize,
+        buffer_len: usize,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        let (format, epp) = self.validate_unpack_buffer_update::<ET>(level, offset, size)?;
+        let required_len = PixelStore::TIGHTLY_PACKED.get_required_len(&size, epp, std::mem::size_of::<ET>())?;
+        if required_len > buffer_len {
+            return Err(format!("A region of size {:?} needs {} values but the pixel unpack buffer only has {}!", size, required_len, buffer_len));
+        }
+        let byte_offset = unwrap_option_or_ret!(buffer_offset.checked_mul(std::mem::size_of::<ET>()), Err("Buffer offset too big!".to_owned()));
+
+        let formatted_offset = to_gl_offset(offset)?;
+        let formatted_siz = to_gl_size(size)?;
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        // NOTE: With a pixel unpack buffer bound the pointer is an offset into the buffer
+        unsafe {
+            internal_gl_tex_sub_image::<N>(TYP, level, formatted_offset, formatted_siz, format, ET::get_gl_type(), byte_offset as *const std::ffi::c_void);
+        }
+        Ok(())
+    }
+
+    /// Returns the size of the mip level the region is in
+    fn validate_region(&self, level: usize, offset: [usize; N], size: [usize; N]) -> Result<[usize; N], String> {
+        let level_size = unwrap_option_or_ret!(self.get_level_size(level), Err(format!("Mip level {} of the texture has not been allocated!", level)));
+        for i in 0..N {
+            if offset[i].checked_add(size[i]).is_none_or(|end| end > level_size[i]) {
+                return Err(format!("Region at offset {:?} of size {:?} does not fit in mip level {} of size {:?}!", offset, size, level, level_size));
+            }
+        }
+        Ok(level_size)
+    }
+
+    /// Uploads data that is already compressed in internal_format ( for example BC7 or ETC2 ), the data has to hold exactly the blocks that cover size
+    /// Level 0 can be of any size, other levels have to be exactly as big as opengl expects them to be given the size of level 0 and in the same internal format
+    pub fn upload_compressed_data_to_texture(
+        &mut self,
+        level: usize,
+        internal_format: GLenum,
+        size: [usize; N],
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.validate_mutable()?;
+        Self::validate_size_for_target(size)?;
+        let internal_fmt = Self::validate_compressed_internal_format(internal_format)?;
+        self.validate_level(level, size, internal_fmt)?;
+        upload_compressed_data_to_target(TYP, level, internal_format, size, data)?;
+        self.record_level(TYP, level, size, internal_fmt, internal_format);
+        Ok(())
+    }
+
+    /// Updates part of mip level 0 of a compressed texture without reallocating it, see update_compressed_region_of_level
+    #[inline(always)]
+    pub fn update_compressed_region(&mut self, offset: [usize; N], size: [usize; N], data: &[u8]) -> Result<(), String> {
+        self.update_compressed_region_of_level(0, offset, size, data)
+    }
+
+    /// The data has to be compressed in the internal format of the texture and the region has to fit inside the mip level
+    /// NOTE: Compressed data is made of whole blocks so the region has to start on a block boundary and end on one too, unless it ends at the edge of the mip level
+    pub fn update_compressed_region_of_level(
+        &mut self,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        data: &[u8],
+    ) -> Result<(), String> {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            return Err("Cube maps have to be updated one face at a time, use update_compressed_cube_face_region instead!".to_owned());
+        }
+        self.update_compressed_region_of_target(TYP, level, offset, size, data)
+    }
 
+    fn update_compressed_region_of_target(
+        &mut self,
+        target: GLenum,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        data: &[u8],
+    ) -> Result<(), String> {
+        let (_, internal_format) = unwrap_option_or_ret!(self.format, Err("Texture has to be allocated before a region of it can be updated!".to_owned()));
+        let (block_w, block_h, _) = unwrap_option_or_ret!(
+            crate::get_compressed_block_info(internal_format),
+            Err("Texture does not have a compressed format, use update_region instead!".to_owned())
+        );
+        let image_size = validate_compressed_data_len(internal_format, &size, data)?;
+        let level_size = self.validate_region(level, offset, size)?;
+        for (i, block_dim) in [block_w, block_h].iter().enumerate().take(N) {
+            let ends_on_edge = offset[i] + size[i] == level_size[i];
+            if !offset[i].is_multiple_of(*block_dim) || (!size[i].is_multiple_of(*block_dim) && !ends_on_edge) {
+                return Err(format!("Region at offset {:?} of size {:?} is not aligned to the {} pixels * {} pixels blocks of the compressed format!", offset, size, block_w, block_h));
+            }
+        }
+        self.validate_bound_to_active_unit()?;
+
+        let formatted_offset = to_gl_offset(offset)?;
+        let formatted_siz = to_gl_size(size)?;
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        unsafe {
+            internal_gl_compressed_tex_sub_image::<N>(
+                target,
+                level,
+                formatted_offset,
+                formatted_siz,
+                internal_format,
+                image_size,
+                data.as_ptr() as *const std::ffi::c_void,
+            );
+        }
+        Ok(())
+    }
+
+    /// Reads a mip level back from opengl in the format the texture was allocated with
+    /// The data returned is tightly packed, whatever PACK_ALIGNMENT is set to
+    /// NOTE: This waits for everything that renders to the texture to finish, so it's slow
+    pub fn download<ET>(&self, level: usize) -> Result<Vec<ET>, String>
+    where
+        ET: HasGLEnum + Default + Clone,
+    {
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            return Err("Cube maps have to be downloaded one face at a time, use download_cube_face instead!".to_owned());
+        }
+        self.download_from_target(TYP, level)
+    }
+
+    fn download_from_target<ET>(&self, target: GLenum, level: usize) -> Result<Vec<ET>, String>
+    where
+        ET: HasGLEnum + Default + Clone,
+    {
+        let (internal_fmt, format) = unwrap_option_or_ret!(self.format, Err("Texture has to be allocated before it can be downloaded!".to_owned()));
+        if self.is_compressed() {
+            return Err("Compressed textures can't be downloaded as pixels!".to_owned());
+        }
+        let internal_fmt: GLenum = unwrap_result_or_ret!(internal_fmt.try_into(), Err("Converting to gl types!".to_owned()));
+        let (_, epp) = get_gl_format_of::<ET>(Some(internal_fmt), format)?;
+        let size = unwrap_option_or_ret!(self.get_level_size(level), Err(format!("Mip level {} of the texture has not been allocated!", level)));
+
+        // Every row but the last one is padded to PACK_ALIGNMENT bytes, rows of 3D and array textures just follow each other
+        let too_big = || format!("Mip level {} of size {:?} is too big to download!", level, size);
+        let row_len = unwrap_option_or_ret!(size[0].checked_mul(usize::from(epp)), Err(too_big()));
+        let rows = unwrap_option_or_ret!(size[1..].iter().try_fold(1usize, |acc, s| acc.checked_mul(*s)), Err(too_big()));
+        let element_size = std::mem::size_of::<ET>();
+        let alignment = get_pack_alignment()?.max(1);
+        let row_bytes = unwrap_option_or_ret!(row_len.checked_mul(element_size), Err(too_big()));
+        let padded_row_len = unwrap_option_or_ret!(row_bytes.checked_add((alignment - row_bytes % alignment) % alignment), Err(too_big())) / element_size;
+        let data_len = unwrap_option_or_ret!(
+            rows.checked_sub(1).and_then(|r| padded_row_len.checked_mul(r)).and_then(|len| len.checked_add(row_len)),
+            Err(too_big())
+        );
+
+        let mut data = vec![ET::default(); data_len];
+        let buf_size = unwrap_option_or_ret!(data_len.checked_mul(element_size), Err(too_big()));
+        let buf_size: GLsizei = unwrap_result_or_ret!(buf_size.try_into(), Err(too_big()));
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        let ptr = data.as_mut_ptr() as *mut std::ffi::c_void;
+        // NOTE: The direct state access functions read from this texture whatever is bound and never write more than buf_size bytes
+        if crate::get_gl_version() >= (4, 5) {
+            if target == TYP {
+                unsafe {
+                    gl::GetTextureImage(self.id, level, format, ET::get_gl_type(), buf_size, ptr);
+                }
+            } else {
+                // A cube map face, which is the layer of the cube map with the index of the face
+                let face: GLint = unwrap_result_or_ret!((target - gl::TEXTURE_CUBE_MAP_POSITIVE_X).try_into(), Err("Converting to gl types!".to_owned()));
+                let formatted_siz = to_gl_size(size)?;
+                unsafe {
+                    gl::GetTextureSubImage(self.id, level, 0, 0, face, formatted_siz[0], formatted_siz[1], 1, format, ET::get_gl_type(), buf_size, ptr);
+                }
+            }
+        } else {
+            // Otherwise glGetTexImage reads from whatever is bound to the active unit, which has to be this texture or the buffer is sized for the wrong one
+            self.validate_bound_to_active_unit()?;
+            unsafe {
+                gl::GetTexImage(target, level, format, ET::get_gl_type(), ptr);
+            }
+        }
+        if padded_row_len != row_len {
+            data = data
+                .chunks(padded_row_len)
+                .flat_map(|row| row[..row_len].iter().cloned())
+                .collect();
+        }
+        Ok(data)
+    }
+
+    /// Checks that this texture is the one bound to its target on the active texture unit, since another bouncer may have changed the active unit since it was bound
+    /// Every upload, update and *_of_bound_tex call acts on whatever is bound there, so they all check this first instead of silently changing another texture
+    fn validate_bound_to_active_unit(&self) -> Result<(), String> {
+        let binding = match TYP {
+            gl::TEXTURE_1D => gl::TEXTURE_BINDING_1D,
+            gl::TEXTURE_1D_ARRAY => gl::TEXTURE_BINDING_1D_ARRAY,
+            gl::TEXTURE_2D => gl::TEXTURE_BINDING_2D,
+            gl::TEXTURE_2D_ARRAY => gl::TEXTURE_BINDING_2D_ARRAY,
+            gl::TEXTURE_3D => gl::TEXTURE_BINDING_3D,
+            gl::TEXTURE_CUBE_MAP => gl::TEXTURE_BINDING_CUBE_MAP,
+            gl::TEXTURE_CUBE_MAP_ARRAY => gl::TEXTURE_BINDING_CUBE_MAP_ARRAY,
+            gl::TEXTURE_RECTANGLE => gl::TEXTURE_BINDING_RECTANGLE,
+            _ => return Err(format!("Unsupported texture target: {:#x}!", TYP)),
+        };
+        let mut bound: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(binding, &mut bound);
+        }
+        if GLuint::try_from(bound) != Ok(self.id) {
+            return Err("Texture is not bound to the active texture unit, another texture unit has been made active since it was bound!".to_owned());
+        }
+        Ok(())
+    }
+
+    pub fn with_data<ET, const BI: usize>(
+        bn: &mut TextureBouncer<BI>,
+        size: [usize; N],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<UnboundTexture<N, TYP>, String>
+    where
+        ET: HasGLEnum,
+    {
+        let mut r = Self::new(bn);
+        {
+            let mut r = r.bind_mut(bn);
+            r.upload_data_to_texture(0, size, data, format)?;
+        }
+        Ok(r)
+    }
+}
+
+impl Texture<2, { gl::TEXTURE_2D }> {
+    /// Copies a region of the framebuffer bound for reading ( the default one or one bound with Framebuffer ) at src_pos into a region of a mip level of the texture
+    /// The pixels are converted to the internal format of the texture, which can't be compressed
+    pub fn copy_from_framebuffer_to_bound_tex(
+        &mut self,
+        level: usize,
+        offset: [usize; 2],
+        src_pos: [usize; 2],
+        size: [usize; 2],
+    ) -> Result<(), String> {
+        if self.format.is_none() {
+            return Err("Texture has to be allocated before it can be copied to!".to_owned());
+        }
+        if self.is_compressed() {
+            return Err("Can't copy from a framebuffer to a texture with a compressed format!".to_owned());
+        }
+        self.validate_region(level, offset, size)?;
+        self.validate_bound_to_active_unit()?;
+        let offset = to_gl_offset(offset)?;
+        let src_pos = to_gl_offset(src_pos)?;
+        let size = to_gl_size(size)?;
+        let level: GLint = unwrap_result_or_ret!(level.try_into(), Err("Mip level too big for opengl!".to_owned()));
+        unsafe {
+            gl::CopyTexSubImage2D(gl::TEXTURE_2D, level, offset[0], offset[1], src_pos[0], src_pos[1], size[0], size[1]);
+        }
+        Ok(())
+    }
+}
+
+impl Texture<2, { gl::TEXTURE_CUBE_MAP }> {
+    /// Level 0 of every face has to be the same size and internal format, so to resize a cube map or change its format use upload_data_to_cube_faces
+    pub fn upload_data_to_cube_face<ET>(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        size: [usize; 2],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        if level == 0 && self.format.is_some() && size != self.size {
+            return Err(format!("Cube map faces must all be the same size, face {:?} is {} pixels * {} pixels but the cube map is {} pixels * {} pixels!", face, size[0], size[1], self.size[0], self.size[1]));
+        }
+        let internal_format = self.get_internal_format().and_then(|internal_fmt| internal_fmt.try_into().ok());
+        self.upload_face(face, level, internal_format, size, data, format)
+    }
+
+    fn upload_face<ET>(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        internal_format: Option<GLenum>,
+        size: [usize; 2],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        if size[0] != size[1] {
+            return Err(format!("Cube map faces must be square, face {:?} is {} pixels * {} pixels!", face, size[0], size[1]));
+        }
+        self.validate_mutable()?;
+        let (internal_fmt, _) = get_gl_format_of::<ET>(internal_format, format)?;
+        self.validate_level(level, size, internal_fmt)?;
+        upload_data_to_target(face.get_gl_target(), level, internal_fmt, size, data, format, &PixelStore::TIGHTLY_PACKED)?;
+        self.record_level(face.get_gl_target(), level, size, internal_fmt, format);
+        Ok(())
+    }
+
+    /// The data is expected in the format the cube map was allocated with and the region has to fit inside the mip level
+    pub fn update_cube_face_region<ET>(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        offset: [usize; 2],
+        size: [usize; 2],
+        data: &[ET],
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.update_region_of_target(face.get_gl_target(), level, offset, size, data, None, &PixelStore::TIGHTLY_PACKED)
+    }
+
+    /// Same as update_cube_face_region but the data is laid out as format ( like gl::BGRA ) instead of the format the texture was allocated with
+    pub fn update_cube_face_region_with_format<ET>(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        offset: [usize; 2],
+        size: [usize; 2],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.update_region_of_target(face.get_gl_target(), level, offset, size, data, Some(format), &PixelStore::TIGHTLY_PACKED)
+    }
+
+    /// Same as update_cube_face_region but the data is laid out as described by layout
+    pub fn update_cube_face_region_with_layout<ET>(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        offset: [usize; 2],
+        size: [usize; 2],
+        data: &[ET],
+        layout: &PixelStore,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.update_region_of_target(face.get_gl_target(), level, offset, size, data, None, layout)
+    }
+
+    /// Same as upload_data_to_cube_face but for data that is already compressed in internal_format
+    /// Level 0 of every face has to be the same size and internal format, so to resize a cube map or change its format start with a new one
+    pub fn upload_compressed_data_to_cube_face(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        internal_format: GLenum,
+        size: [usize; 2],
+        data: &[u8],
+    ) -> Result<(), String> {
+        if size[0] != size[1] {
+            return Err(format!("Cube map faces must be square, face {:?} is {} pixels * {} pixels!", face, size[0], size[1]));
+        }
+        self.validate_mutable()?;
+        let internal_fmt = Self::validate_compressed_internal_format(internal_format)?;
+        if let (0, Some((tex_internal_fmt, _))) = (level, self.format) {
+            if size != self.size || internal_fmt != tex_internal_fmt {
+                return Err(format!("Cube map faces must all be the same size and internal format, face {:?} is {} pixels * {} pixels in format {:#x} but the cube map is {} pixels * {} pixels in format {:#x}!", face, size[0], size[1], internal_fmt, self.size[0], self.size[1], tex_internal_fmt));
+            }
+        }
+        self.validate_level(level, size, internal_fmt)?;
+        upload_compressed_data_to_target(face.get_gl_target(), level, internal_format, size, data)?;
+        self.record_level(face.get_gl_target(), level, size, internal_fmt, internal_format);
+        Ok(())
+    }
+
+    /// The data has to be compressed in the internal format of the cube map and the region has to fit inside the mip level and be aligned to blocks
+    pub fn update_compressed_cube_face_region(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        offset: [usize; 2],
+        size: [usize; 2],
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.update_compressed_region_of_target(face.get_gl_target(), level, offset, size, data)
+    }
+
+    /// Same as download but for one face of the cube map
+    #[inline(always)]
+    pub fn download_cube_face<ET>(&self, face: CubeFace, level: usize) -> Result<Vec<ET>, String>
+    where
+        ET: HasGLEnum + Default + Clone,
+    {
+        self.download_from_target(face.get_gl_target(), level)
+    }
+
+    /// Faces are expected in the same order as CubeFace::ALL
+    /// Level 0 is stored in the default internal format for the type and format of the data, other levels in the same internal format as level 0
+    #[inline(always)]
+    pub fn upload_data_to_cube_faces<ET>(
+        &mut self,
+        level: usize,
+        faces: [([usize; 2], &[ET]); 6],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_faces(level, None, faces, format)
+    }
+
+    /// Same as upload_data_to_cube_faces but the data is converted to and stored as internal_format
+    #[inline(always)]
+    pub fn upload_data_to_cube_faces_with_internal_format<ET>(
+        &mut self,
+        level: usize,
+        internal_format: GLenum,
+        faces: [([usize; 2], &[ET]); 6],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_faces(level, Some(internal_format), faces, format)
+    }
+
+    fn upload_faces<ET>(
+        &mut self,
+        level: usize,
+        internal_format: Option<GLenum>,
+        faces: [([usize; 2], &[ET]); 6],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        // Validate everything before uploading anything so we don't end up with a half uploaded cube map
+        let size = faces[0].0;
+        for (face, (face_size, _)) in CubeFace::ALL.iter().zip(faces.iter()) {
+            if face_size[0] != face_size[1] {
+                return Err(format!("Cube map faces must be square, face {:?} is {} pixels * {} pixels!", face, face_size[0], face_size[1]));
+            }
+            if *face_size != size {
+                return Err(format!("Cube map faces must all be the same size, face {:?} is {} pixels * {} pixels but face {:?} is {} pixels * {} pixels!", face, face_size[0], face_size[1], CubeFace::ALL[0], size[0], size[1]));
+            }
+        }
+        let internal_format = self.get_internal_format_for_level(level, internal_format);
+        get_gl_format_of::<ET>(internal_format, format)?;
+        for (face, (face_size, data)) in CubeFace::ALL.iter().zip(faces.iter()) {
+            self.upload_face(*face, level, internal_format, *face_size, data, format)?;
+        }
+        Ok(())
+    }
+
+    pub fn with_faces<ET, const BI: usize>(
+        bn: &mut TextureBouncer<BI>,
+        faces: [([usize; 2], &[ET]); 6],
+        format: GLenum,
+    ) -> Result<UnboundTexture<2, { gl::TEXTURE_CUBE_MAP }>, String>
+    where
+        ET: HasGLEnum,
+    {
+        let mut r = Self::new(bn);
+        {
+            let mut r = r.bind_mut(bn);
+            r.upload_data_to_cube_faces(0, faces, format)?;
+        }
+        Ok(r)
+    }
+}
+
+impl<const TYP: GLenum> Texture<3, TYP> {
+    /// Uploads one layer of a 2D array texture or one slice of a 3D texture
+    fn upload_layer<ET>(
+        &mut self,
+        layer: usize,
+        size: [usize; 2],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        if self.format.is_some_and(|(_, tex_format)| tex_format != format) {
+            return Err(format!("Format of data provided ({:#x}) does not match the format the texture was allocated with!", format));
+        }
+        self.update_region([0, 0, layer], [size[0], size[1], 1], data)
+    }
+}
+
+impl Texture<3, { gl::TEXTURE_2D_ARRAY }> {
+    /// The storage of the texture must have already been allocated ( see allocate_texture_data ) with the same format and be big enough to hold the layer
+    pub fn upload_layer_to_texture<ET>(
+        &mut self,
+        layer: usize,
+        size: [usize; 2],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_layer(layer, size, data, format)
+    }
+
+    /// Creates an array texture with one layer for each slice in layers
+    pub fn with_layers<ET, const BI: usize>(
+        bn: &mut TextureBouncer<BI>,
+        size: [usize; 2],
+        layers: &[&[ET]],
+        format: GLenum,
+    ) -> Result<UnboundTexture<3, { gl::TEXTURE_2D_ARRAY }>, String>
+    where
+        ET: HasGLEnum,
+    {
+        let mut r = Self::new(bn);
+        {
+            let mut r = r.bind_mut(bn);
+            r.allocate_texture_data::<ET>([size[0], size[1], layers.len()], format)?;
+            for (i, layer) in layers.iter().enumerate() {
+                r.upload_layer_to_texture(i, size, layer, format)?;
+            }
+        }
+        Ok(r)
+    }
+}
+
+impl Texture<3, { gl::TEXTURE_3D }> {
+    /// The storage of the texture must have already been allocated ( see allocate_texture_data ) with the same format and be big enough to hold the slice
+    pub fn upload_slice_to_texture<ET>(
+        &mut self,
+        slice: usize,
+        size: [usize; 2],
+        data: &[ET],
+        format: GLenum,
+    ) -> Result<(), String>
+    where
+        ET: HasGLEnum,
+    {
+        self.upload_layer(slice, size, data, format)
+    }
+}
+
+impl LayeredTexture for Texture<2, { gl::TEXTURE_1D_ARRAY }> {
+    #[inline(always)]
+    fn get_num_of_layers(&self, level: usize) -> Option<usize> {
+        self.get_level_size(level).map(|size| size[1])
+    }
+}
+
+impl LayeredTexture for Texture<2, { gl::TEXTURE_CUBE_MAP }> {
+    #[inline(always)]
+    fn get_num_of_layers(&self, level: usize) -> Option<usize> {
+        self.get_level_size(level).map(|_| CubeFace::ALL.len())
+    }
+}
+
+impl<const TYP: GLenum> LayeredTexture for Texture<3, TYP> {
+    #[inline(always)]
+    fn get_num_of_layers(&self, level: usize) -> Option<usize> {
+        self.get_level_size(level).map(|size| size[2])
+    }
+}
+
+impl<const N: usize, const TYP: GLenum> sealed::CopyableImageId for Texture<N, TYP> {
+    #[inline(always)]
+    fn get_copy_id(&self) -> GLuint {
+        self.id
+    }
+}
+
+impl<const N: usize, const TYP: GLenum> CopyableImage for Texture<N, TYP> {
+    #[inline(always)]
+    fn get_copy_target(&self) -> GLenum {
+        TYP
+    }
+
+    #[inline(always)]
+    fn get_copy_internal_format(&self) -> Option<GLenum> {
+        self.get_internal_format().and_then(|internal_fmt| internal_fmt.try_into().ok())
+    }
+
+    /// The dimensions the texture doesn't have are 1, and cube maps have 6 faces
+    fn get_copy_level_size(&self, level: usize) -> Option<[usize; 3]> {
+        let level_size = self.get_level_size(level)?;
+        let mut size = [1; 3];
+        size[..N].copy_from_slice(&level_size);
+        if TYP == gl::TEXTURE_CUBE_MAP {
+            size[2] = 6;
+        }
+        Some(size)
+    }
+}
+
+impl<const N: usize, const TYP: GLenum> Drop for Texture<N, TYP> {
+    fn drop(&mut self) {
+        // Handles made from the texture become invalid once it's deleted
+        self.resident_handles.release_all();
+        unsafe {
+            gl::DeleteTextures(1, &self.id);
+        }
+    }
+}
+
+}
+
+pub(crate) use priv_texture::{get_active_texture_unit, set_active_texture_unit};
+
+pub type Texture1D = priv_texture::Texture<1, { gl::TEXTURE_1D }>;
+/// The second dimension of a 1D array texture is the number of layers
+pub type Texture1DArr = priv_texture::Texture<2, { gl::TEXTURE_1D_ARRAY }>;
 pub type Texture2D = priv_texture::Texture<2, { gl::TEXTURE_2D }>;
 pub type Texture2DArr = priv_texture::Texture<3, { gl::TEXTURE_2D_ARRAY }>;
 pub type Texture3D = priv_texture::Texture<3, { gl::TEXTURE_3D }>;
+pub type TextureCube = priv_texture::Texture<2, { gl::TEXTURE_CUBE_MAP }>;
+/// The depth of a cube map array is in layer-faces, so it should be 6 * the number of cubes
+pub type TextureCubeArray = priv_texture::Texture<3, { gl::TEXTURE_CUBE_MAP_ARRAY }>;
+
+pub type TextureBouncer<const SLOT: usize> = priv_texture::TextureBouncer<SLOT>;
+pub type Texture<const N: usize, const TYP: GLenum> = priv_texture::Texture<N, TYP>;
+pub type UnboundTexture<const N: usize, const TYP: GLenum> = priv_texture::UnboundTexture<N, TYP>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tightly_packed_len() {
+        assert_eq!(PixelStore::TIGHTLY_PACKED.get_required_len(&[3, 2], 3, 1), Ok(18));
+        assert_eq!(PixelStore::TIGHTLY_PACKED.get_required_len(&[3, 2, 4], 4, 4), Ok(96));
+        assert_eq!(PixelStore::TIGHTLY_PACKED.get_required_len(&[0, 2], 4, 1), Ok(0));
+    }
+
+    #[test]
+    fn alignment_pads_rows_of_small_elements() {
+        // 9 byte rows are padded to 12, the last row isn't padded
+        assert_eq!(PixelStore::new().with_alignment(4).get_required_len(&[3, 2], 3, 1), Ok(21));
+        // 12 byte rows are padded to 16 with 8 byte alignment, 28 bytes is 7 f32s
+        assert_eq!(PixelStore::new().with_alignment(8).get_required_len(&[1, 2], 3, 4), Ok(7));
+        // Elements that are at least as big as the alignment are never padded
+        assert_eq!(PixelStore::new().with_alignment(4).get_required_len(&[3, 2], 3, 4), Ok(18));
+        assert_eq!(PixelStore::new().with_alignment(2).get_required_len(&[3, 2], 1, 2), Ok(6));
+    }
 
-pub type TextureBouncer<const SLOT: usize> = priv_texture::TextureBouncer<SLOT>;
\ No newline at end of file
+    #[test]
+    fn row_length_with_skipped_pixels_and_rows() {
+        let layout = PixelStore::new().with_row_length(5).with_skip_pixels(3).with_skip_rows(1);
+        // 3 skipped pixels, 2 rows of 5 pixels ( the skipped one and the first of the region ) and the 2 pixels of the last row
+        assert_eq!(layout.get_required_len(&[2, 2], 1, 1), Ok(15));
+        assert_eq!(layout.get_required_len(&[2, 2], 4, 1), Ok(60));
+        assert_eq!(layout.with_alignment(8).get_required_len(&[2, 2], 1, 1), Ok(3 + 2 * 8 + 2));
+    }
+
+    #[test]
+    fn one_dimensional_ignores_rows_and_images() {
+        let layout = PixelStore::new().with_row_length(3).with_skip_rows(5).with_skip_images(5).with_image_height(1);
+        assert_eq!(layout.get_required_len(&[4], 1, 1), Ok(4));
+        assert_eq!(layout.with_skip_pixels(2).get_required_len(&[4], 1, 1), Ok(6));
+    }
+
+    #[test]
+    fn three_dimensional_image_height_and_skip_images() {
+        let layout = PixelStore::new().with_image_height(3).with_skip_rows(1).with_skip_images(1);
+        // Images are 3 rows of 2 pixels, the region starts a row into the second image and ends 2 rows into the third
+        assert_eq!(layout.get_required_len(&[2, 2, 2], 1, 1), Ok(18));
+        // 2D regions ignore the image height and skipped images
+        assert_eq!(layout.get_required_len(&[2, 2], 1, 1), Ok(6));
+        assert!(PixelStore::new().with_image_height(2).with_skip_rows(1).get_required_len(&[1, 2, 1], 1, 1).is_err());
+    }
+
+    #[test]
+    fn invalid_layouts() {
+        assert!(PixelStore::new().with_row_length(4).with_skip_pixels(2).get_required_len(&[3, 1], 1, 1).is_err());
+        assert!(PixelStore::new().with_row_length(2).get_required_len(&[3, 1], 1, 1).is_err());
+        assert!(PixelStore::new().with_alignment(3).get_required_len(&[3, 1], 1, 1).is_err());
+        assert!(PixelStore::new().with_alignment(0).get_required_len(&[3, 1], 1, 1).is_err());
+        assert!(PixelStore::new().get_required_len(&[usize::MAX, 2], 4, 1).is_err());
+        assert!(PixelStore::new().with_skip_images(usize::MAX).get_required_len(&[1, 1, 2], 1, 1).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::render::texture_unit::UnitBinding;
+use crate::util::buffer_obj::BOFunc;
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret, HasGLEnum};
+use gl::types::*;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::sync::Arc;
+use one_user::one_user;
+
+impl<ET> texturebuffer_binder::OnBind for TextureBuffer<ET> {
+    #[inline(always)]
+    fn on_bind<const SLOT: usize>(&self) {
+        crate::render::texture_unit::assert_bouncer_unit_not_reserved(SLOT);
+        crate::render::texture::set_active_texture_unit(SLOT);
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_BUFFER, self.id);
+        }
+    }
+}
+
+/// Returns the internal format a buffer of ET with components elements per texel is read as
+/// Floats are read with samplerBuffer, unsigned integers with usamplerBuffer and signed integers with isamplerBuffer
+/// NOTE: Texels of 3 components are only possible with 32 bit types and need opengl 4.0 ( or ARB_texture_buffer_object_rgb32 )
+pub fn get_texture_buffer_internal_format<ET: HasGLEnum>(components: u8) -> Option<GLenum> {
+    let formats = match ET::get_gl_type() {
+        gl::FLOAT => [gl::R32F, gl::RG32F, gl::RGB32F, gl::RGBA32F],
+        gl::HALF_FLOAT => [gl::R16F, gl::RG16F, gl::NONE, gl::RGBA16F],
+        gl::UNSIGNED_INT => [gl::R32UI, gl::RG32UI, gl::RGB32UI, gl::RGBA32UI],
+        gl::INT => [gl::R32I, gl::RG32I, gl::RGB32I, gl::RGBA32I],
+        gl::UNSIGNED_SHORT => [gl::R16UI, gl::RG16UI, gl::NONE, gl::RGBA16UI],
+        gl::SHORT => [gl::R16I, gl::RG16I, gl::NONE, gl::RGBA16I],
+        gl::UNSIGNED_BYTE => [gl::R8UI, gl::RG8UI, gl::NONE, gl::RGBA8UI],
+        gl::BYTE => [gl::R8I, gl::RG8I, gl::NONE, gl::RGBA8I],
+        _ => return None,
+    };
+    let format = *formats.get(usize::from(components).checked_sub(1)?)?;
+    if format == gl::NONE {
+        None
+    } else {
+        Some(format)
+    }
+}
+
+/// A texture that reads its texels straight out of a buffer object, for big arrays of data ( like per instance data ) that don't fit in uniforms
+/// NOTE: It binds to the same texture units as textures do, but to its own target, so a unit can have both a texture and a texture buffer bound at the same time
+#[one_user(256)]
+pub struct TextureBuffer<ET> {
+    id: GLuint,
+    /// The number of texels that can be read, 0 until a buffer has been attached
+    size: usize,
+    /// None until a buffer has been attached
+    internal_format: Option<GLenum>,
+    data: PhantomData<ET>,
+    /// Only here so UnitBindings can tell if the texture buffer has been dropped
+    alive: Arc<()>,
+}
+
+impl<ET> Drop for TextureBuffer<ET> {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteTextures(1, &self.id);
+        }
+    }
+}
+
+impl<ET> TextureBuffer<ET>
+where
+    ET: HasGLEnum,
+{
+    pub fn new() -> UnboundTextureBuffer<ET> {
+        let mut r = TextureBuffer {
+            id: 0,
+            size: 0,
+            internal_format: None,
+            data: PhantomData,
+            alive: Arc::new(()),
+        };
+        unsafe {
+            gl::GenTextures(1, &mut r.id);
+        }
+        UnboundTextureBuffer::from(r)
+    }
+
+    pub fn with_buffer<B, const BI: usize>(bn: &mut TextureBufferBouncer<BI>, bo: &B, components: u8) -> Result<UnboundTextureBuffer<ET>, String>
+    where
+        B: BOFunc<ET>,
+    {
+        let mut r = Self::new();
+        {
+            let mut r = r.bind_mut(bn);
+            r.attach_buffer(bo, components)?;
+        }
+        Ok(r)
+    }
+
+    fn validate_texels(components: u8, elements: usize) -> Result<(GLenum, usize), String> {
+        let internal_format = unwrap_option_or_ret!(
+            get_texture_buffer_internal_format::<ET>(components),
+            Err(format!("Buffers of type {:#x} can't be read with {} components per texel!", ET::get_gl_type(), components))
+        );
+        if !elements.is_multiple_of(usize::from(components)) {
+            return Err(format!("{} elements can't be split into texels of {} components!", elements, components));
+        }
+        let texels = elements / usize::from(components);
+        let mut max_size: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_TEXTURE_BUFFER_SIZE, &mut max_size);
+        }
+        let max_size: usize = unwrap_result_or_ret!(max_size.try_into(), Err("Max texture buffer size returned from opengl is negative, faulty opengl implementation!".to_owned()));
+        if texels > max_size {
+            return Err(format!("Texture buffers can have at most {} texels, not {}!", max_size, texels));
+        }
+        Ok((internal_format, texels))
+    }
+
+    /// Makes the texture read the whole buffer, components is the number of elements in a texel ( 1 to 4 )
+    /// NOTE: The buffer is kept alive by opengl for as long as it's attached, but changing its size means it has to be attached again
+    pub fn attach_buffer<B>(&mut self, bo: &B, components: u8) -> Result<(), String>
+    where
+        B: BOFunc<ET>,
+    {
+        let elements: usize = unwrap_result_or_ret!(bo.get_size().try_into(), Err("Buffer size malformed!".to_owned()));
+        let (internal_format, texels) = Self::validate_texels(components, elements)?;
+        unsafe {
+            gl::TexBuffer(gl::TEXTURE_BUFFER, internal_format, bo.get_bo_base().get_id());
+        }
+        self.size = texels;
+        self.internal_format = Some(internal_format);
+        Ok(())
+    }
+
+    /// Same as attach_buffer but only a range of the buffer is read, offset and len are in elements
+    /// NOTE: Needs opengl 4.3 ( or ARB_texture_buffer_range ), and the offset in bytes has to be a multiple of GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT
+    pub fn attach_buffer_range<B>(&mut self, bo: &B, components: u8, offset: usize, len: usize) -> Result<(), String>
+    where
+        B: BOFunc<ET>,
+    {
+        let elements: usize = unwrap_result_or_ret!(bo.get_size().try_into(), Err("Buffer size malformed!".to_owned()));
+        if offset.checked_add(len).is_none_or(|end| end > elements) {
+            return Err(format!("Range at offset {} of {} elements does not fit in a buffer of {} elements!", offset, len, elements));
+        }
+        let (internal_format, texels) = Self::validate_texels(components, len)?;
+        let mut alignment: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::TEXTURE_BUFFER_OFFSET_ALIGNMENT, &mut alignment);
+        }
+        let alignment: usize = unwrap_result_or_ret!(alignment.try_into(), Err("Texture buffer offset alignment returned from opengl is negative, faulty opengl implementation!".to_owned()));
+        // NOTE: Both fit since the range is inside the buffer, whose size in bytes fits in a GLsizeiptr
+        let (offset, len) = (offset * size_of::<ET>(), len * size_of::<ET>());
+        if alignment != 0 && !offset.is_multiple_of(alignment) {
+            return Err(format!("Offset of {} bytes is not a multiple of the texture buffer offset alignment of {} bytes!", offset, alignment));
+        }
+        unsafe {
+            gl::TexBufferRange(
+                gl::TEXTURE_BUFFER,
+                internal_format,
+                bo.get_bo_base().get_id(),
+                offset.try_into().expect("Converting to gl types!"),
+                len.try_into().expect("Converting to gl types!"),
+            );
+        }
+        self.size = texels;
+        self.internal_format = Some(internal_format);
+        Ok(())
+    }
+
+    /// What a TextureUnitAllocator needs to bind the texture buffer to a unit chosen at runtime
+    #[inline(always)]
+    pub fn get_unit_binding(&self) -> UnitBinding {
+        UnitBinding::new(gl::TEXTURE_BUFFER, self.id, &self.alive)
+    }
+
+    /// The number of texels that can be read, 0 if no buffer has been attached yet
+    #[inline(always)]
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// None if no buffer has been attached yet
+    #[inline(always)]
+    pub fn get_internal_format(&self) -> Option<GLenum> {
+        self.internal_format
+    }
+}
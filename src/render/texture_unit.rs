@@ -0,0 +1,205 @@
+use crate::unwrap_result_or_ret;
+use gl::types::*;
+use std::cell::RefCell;
+use std::convert::TryInto;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+/// What a TextureUnitAllocator needs to bind a texture or texture buffer, get it once ( while the texture is bound ) and keep it next to the unbound texture
+#[derive(Clone, Debug)]
+pub struct UnitBinding {
+    target: GLenum,
+    id: GLuint,
+    /// Dead once the texture has been dropped, so its id is never bound after it has been deleted ( and maybe reused )
+    alive: Weak<()>,
+}
+
+impl UnitBinding {
+    // NEEDED BY Texture and TextureBuffer
+    #[inline(always)]
+    pub(crate) fn new(target: GLenum, id: GLuint, alive: &Arc<()>) -> Self {
+        UnitBinding {
+            target,
+            id,
+            alive: Arc::downgrade(alive),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_target(&self) -> GLenum {
+        self.target
+    }
+
+    /// False once the texture has been dropped
+    #[inline(always)]
+    pub fn is_alive(&self) -> bool {
+        self.alive.strong_count() != 0
+    }
+}
+
+static ALLOCATOR_CREATED: AtomicBool = AtomicBool::new(false);
+/// The first unit of the allocator that exists, usize::MAX if there is none
+static FIRST_RESERVED_UNIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+/// One past the highest unit a texture or texture buffer has been bound to with a bouncer
+static BOUNCER_UNITS_USED: AtomicUsize = AtomicUsize::new(0);
+
+// NEEDED BY Texture and TextureBuffer
+/// Panics if the unit of a bouncer is one of the units reserved by the allocator, since binding there would swap out a texture the allocator bound
+/// ( and the other way around, the allocator would swap out the texture of a live Bound )
+#[inline(always)]
+pub(crate) fn assert_bouncer_unit_not_reserved(slot: usize) {
+    BOUNCER_UNITS_USED.fetch_max(slot + 1, Ordering::SeqCst);
+    assert!(
+        slot < FIRST_RESERVED_UNIT.load(Ordering::SeqCst),
+        "Texture unit {} is reserved by the texture unit allocator, bouncers can only use the units below its first unit!",
+        slot
+    );
+}
+
+/// Hands out texture units at runtime, for when the number of textures isn't known at compile time ( like data driven materials )
+/// Units are given out from first_unit up to GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS, the ones below first_unit are left for TextureBouncers and TextureBufferBouncers
+/// While the allocator exists binding a texture or texture buffer with a bouncer whose slot is first_unit or above panics
+pub struct TextureUnitAllocator {
+    first_unit: usize,
+    /// One for every unit the allocator can hand out, true if it's in use
+    used: RefCell<Vec<bool>>,
+}
+
+impl Drop for TextureUnitAllocator {
+    fn drop(&mut self) {
+        FIRST_RESERVED_UNIT.store(usize::MAX, Ordering::SeqCst);
+        ALLOCATOR_CREATED.store(false, Ordering::SeqCst);
+    }
+}
+
+impl TextureUnitAllocator {
+    /// IMPORTANT: Only one allocator can exist at a time
+    /// Fails if a bouncer has already bound a texture or texture buffer to first_unit or above
+    pub fn new(first_unit: usize) -> Result<Self, String> {
+        let mut max_units: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS, &mut max_units);
+        }
+        let max_units: usize = unwrap_result_or_ret!(max_units.try_into(), Err("Max combined texture image units returned from opengl is negative, faulty opengl implementation!".to_owned()));
+        if first_unit >= max_units {
+            return Err(format!("First unit {} is out of bounds, there are only {} texture units!", first_unit, max_units));
+        }
+        let bouncer_units = BOUNCER_UNITS_USED.load(Ordering::SeqCst);
+        if first_unit < bouncer_units {
+            return Err(format!("Bouncers already use texture units up to {}, the allocator has to start at unit {} or above!", bouncer_units - 1, bouncer_units));
+        }
+        if ALLOCATOR_CREATED.swap(true, Ordering::SeqCst) {
+            return Err("Texture unit allocator already created!".to_owned());
+        }
+        FIRST_RESERVED_UNIT.store(first_unit, Ordering::SeqCst);
+        Ok(TextureUnitAllocator {
+            first_unit,
+            used: RefCell::new(vec![false; max_units - first_unit]),
+        })
+    }
+
+    #[inline(always)]
+    pub fn get_first_unit(&self) -> usize {
+        self.first_unit
+    }
+
+    /// The number of units the allocator can hand out in total
+    #[inline(always)]
+    pub fn get_num_of_units(&self) -> usize {
+        self.used.borrow().len()
+    }
+
+    /// The number of units that aren't in use right now
+    pub fn get_num_of_free_units(&self) -> usize {
+        self.used.borrow().iter().filter(|used| !**used).count()
+    }
+
+    /// Returns the index ( relative to first_unit ) of the first run of count free units, they have to be consecutive for glBindTextures
+    fn find_free_units(used: &[bool], count: usize) -> Option<usize> {
+        let mut run_start = 0;
+        for (i, used) in used.iter().enumerate() {
+            if *used {
+                run_start = i + 1;
+            } else if i + 1 - run_start == count {
+                return Some(run_start);
+            }
+        }
+        None
+    }
+
+    /// Binds every texture to its own unit, the units stay in use until the returned BoundTextureUnits is dropped
+    /// Uses glBindTextures to bind them all in one call when it's available ( opengl 4.4 or ARB_multi_bind )
+    pub fn bind_textures(&self, textures: &[UnitBinding]) -> Result<BoundTextureUnits<'_>, String> {
+        if textures.is_empty() {
+            return Err("At least one texture is needed!".to_owned());
+        }
+        if let Some(i) = textures.iter().position(|tex| !tex.is_alive()) {
+            return Err(format!("Texture {} has been dropped, it can't be bound!", i));
+        }
+        let first = {
+            let mut used = self.used.borrow_mut();
+            let start = match Self::find_free_units(&used, textures.len()) {
+                Some(start) => start,
+                None => return Err(format!("There are no {} consecutive free texture units left!", textures.len())),
+            };
+            used[start..start + textures.len()].iter_mut().for_each(|used| *used = true);
+            self.first_unit + start
+        };
+        let units = first..first + textures.len();
+
+        if crate::get_gl_version() >= (4, 4) || crate::is_gl_extension_supported("GL_ARB_multi_bind") {
+            let ids = textures.iter().map(|tex| tex.id).collect::<Vec<GLuint>>();
+            unsafe {
+                gl::BindTextures(
+                    first.try_into().expect("Converting to gl types!"),
+                    textures.len().try_into().expect("Converting to gl types!"),
+                    ids.as_ptr(),
+                );
+            }
+        } else {
+            // NOTE: The active unit is put back afterwards, glBindTextures leaves it untouched so both paths have to behave the same
+            let previous_unit = crate::render::texture::get_active_texture_unit();
+            for (unit, tex) in units.clone().zip(textures.iter()) {
+                crate::render::texture::set_active_texture_unit(unit);
+                unsafe {
+                    gl::BindTexture(tex.target, tex.id);
+                }
+            }
+            crate::render::texture::set_active_texture_unit(previous_unit);
+        }
+        Ok(BoundTextureUnits { allocator: self, units })
+    }
+}
+
+/// The units a group of textures is bound to, they are given back to the allocator when this is dropped
+/// NOTE: The textures stay bound after that until something else is bound to their units
+pub struct BoundTextureUnits<'a> {
+    allocator: &'a TextureUnitAllocator,
+    units: Range<usize>,
+}
+
+impl BoundTextureUnits<'_> {
+    /// The unit of every texture, in the order they were given to bind_textures
+    #[inline(always)]
+    pub fn get_units(&self) -> Range<usize> {
+        self.units.clone()
+    }
+
+    /// The unit of the texture at index as the value a sampler uniform expects, None if index is out of bounds
+    #[inline(always)]
+    pub fn get_sampler_value(&self, index: usize) -> Option<GLint> {
+        if index >= self.units.len() {
+            return None;
+        }
+        (self.units.start + index).try_into().ok()
+    }
+}
+
+impl Drop for BoundTextureUnits<'_> {
+    fn drop(&mut self) {
+        let start = self.units.start - self.allocator.first_unit;
+        let end = self.units.end - self.allocator.first_unit;
+        self.allocator.used.borrow_mut()[start..end].iter_mut().for_each(|used| *used = false);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{unwrap_result_or_ret, HasGLEnum};
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret, HasGLEnum};
 use gl::types::*;
 use std::convert::TryFrom;
 use std::marker::PhantomData;
@@ -26,7 +26,8 @@ pub struct BOBase<ET> {
 }
 
 impl<ET> BOBase<ET> {
-    fn new() -> Self {
+    // NEEDED BY PixelUnpackRing
+    pub(crate) fn new() -> Self {
         let mut r = BOBase::<ET> {
             id: 0,
             size: 0,
@@ -37,6 +38,30 @@ impl<ET> BOBase<ET> {
         }
         r
     }
+
+    // NEEDED BY TextureBuffer
+    #[inline(always)]
+    pub(crate) fn get_id(&self) -> GLuint {
+        self.id
+    }
+
+    // NEEDED BY PixelUnpackRing
+    /// Allocates room for len elements without uploading anything, the buffer has to be bound to target
+    pub(crate) fn allocate(&mut self, target: GLenum, len: usize, usage: GLenum) -> Result<(), String> {
+        let size = unwrap_result_or_ret!(
+            GLsizeiptr::try_from(len),
+            Err("Too many elements for opengl!".to_owned())
+        );
+        let bytes = unwrap_option_or_ret!(
+            GLsizeiptr::try_from(size_of::<ET>()).ok().and_then(|elem_size| size.checked_mul(elem_size)),
+            Err("Buffer too big for opengl!".to_owned())
+        );
+        unsafe {
+            gl::BufferData(target, bytes, std::ptr::null(), usage);
+        }
+        self.size = size;
+        Ok(())
+    }
 }
 
 impl<ET> Drop for BOBase<ET> {
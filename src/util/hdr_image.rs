@@ -0,0 +1,206 @@
+use crate::render::texture::{CubeFace, Texture2D, TextureBouncer, TextureCube, UnboundTexture};
+use crate::{unwrap_result_or_ret, Half};
+use image::codecs::hdr::HdrDecoder;
+use image::codecs::openexr::OpenExrDecoder;
+use image::DynamicImage;
+use std::convert::TryInto;
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek};
+use std::path::Path;
+
+/// A high dynamic range image ( like an environment map ) loaded from a Radiance .hdr or an OpenEXR file
+/// Images with 3 channels become RGB16F textures and images with 4 become RGBA32F textures
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatImage {
+    size: [usize; 2],
+    /// 3 ( RGB ) or 4 ( RGBA )
+    channels: u8,
+    /// Top row first, like the image is stored
+    data: Vec<f32>,
+}
+
+/// The data of an image as opengl gets it for its number of channels, RGB becomes half floats to save memory
+enum UploadData {
+    Rgb(Vec<Half>),
+    Rgba(Vec<f32>),
+}
+
+impl FloatImage {
+    fn new(size: [u32; 2], channels: u8, data: Vec<f32>) -> Result<Self, String> {
+        let size: [usize; 2] = [
+            unwrap_result_or_ret!(size[0].try_into(), Err("Image width too big!".to_owned())),
+            unwrap_result_or_ret!(size[1].try_into(), Err("Image height too big!".to_owned())),
+        ];
+        if size.contains(&0) {
+            return Err("Image is empty!".to_owned());
+        }
+        if data.len() != size[0] * size[1] * usize::from(channels) {
+            return Err(format!("Image of size {:?} with {} channels does not have {} values!", size, channels, data.len()));
+        }
+        Ok(FloatImage { size, channels, data })
+    }
+
+    /// Reads a Radiance RGBE image, which never has an alpha channel
+    pub fn from_hdr<R: BufRead>(reader: R) -> Result<Self, String> {
+        let decoder = unwrap_result_or_ret!(HdrDecoder::new(reader), Err("Malformed hdr image!".to_owned()));
+        let meta = decoder.metadata();
+        let pixels = unwrap_result_or_ret!(decoder.read_image_hdr(), Err("Malformed hdr image data!".to_owned()));
+        let data = pixels.iter().flat_map(|pixel| pixel.0).collect();
+        Self::new([meta.width, meta.height], 3, data)
+    }
+
+    /// Reads an OpenEXR image, it has an alpha channel only if the file has one
+    pub fn from_exr<R: BufRead + Seek>(reader: R) -> Result<Self, String> {
+        let decoder = unwrap_result_or_ret!(OpenExrDecoder::new(reader), Err("Malformed exr image!".to_owned()));
+        let img = unwrap_result_or_ret!(DynamicImage::from_decoder(decoder), Err("Malformed exr image data!".to_owned()));
+        let size = [img.width(), img.height()];
+        match img {
+            DynamicImage::ImageRgb32F(buf) => Self::new(size, 3, buf.into_raw()),
+            DynamicImage::ImageRgba32F(buf) => Self::new(size, 4, buf.into_raw()),
+            _ => Err(format!("Unsupported exr color type: {:?}!", img.color())),
+        }
+    }
+
+    /// Picks the loader from the extension of the file ( .hdr or .exr )
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file = unwrap_result_or_ret!(File::open(path), Err(format!("Could not open {}!", path.display())));
+        let reader = BufReader::new(file);
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()).as_deref() {
+            Some("hdr") => Self::from_hdr(reader),
+            Some("exr") => Self::from_exr(reader),
+            _ => Err(format!("Unknown hdr image extension of {}, expected .hdr or .exr!", path.display())),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_size(&self) -> [usize; 2] {
+        self.size
+    }
+
+    #[inline(always)]
+    pub fn get_channels(&self) -> u8 {
+        self.channels
+    }
+
+    /// The values of every channel of every pixel, top row first
+    #[inline(always)]
+    pub fn get_data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Images are stored top row first while opengl expects the bottom row first, so this should be done before creating a 2D texture unless the texture coordinates account for that
+    pub fn flip_vertically(&mut self) {
+        let row_len = self.size[0] * usize::from(self.channels);
+        let rows = self.size[1];
+        for y in 0..rows / 2 {
+            let (top, bottom) = self.data.split_at_mut((rows - 1 - y) * row_len);
+            top[y * row_len..(y + 1) * row_len].swap_with_slice(&mut bottom[..row_len]);
+        }
+    }
+
+    fn to_upload_data(channels: u8, data: &[f32]) -> UploadData {
+        if channels == 3 {
+            UploadData::Rgb(data.iter().map(|val| Half::from_f32(*val)).collect())
+        } else {
+            UploadData::Rgba(data.to_vec())
+        }
+    }
+
+    pub fn create_texture_2d<const BI: usize>(&self, bn: &mut TextureBouncer<BI>) -> Result<UnboundTexture<2, { gl::TEXTURE_2D }>, String> {
+        match Self::to_upload_data(self.channels, &self.data) {
+            UploadData::Rgb(data) => Texture2D::with_data(bn, self.size, &data, gl::RGB),
+            UploadData::Rgba(data) => Texture2D::with_data(bn, self.size, &data, gl::RGBA),
+        }
+    }
+
+    /// Creates a cube map from one image per face in CubeFace::ALL order, they have to be square, the same size and have the same number of channels
+    /// NOTE: Cube map faces are stored top row first, so unlike 2D textures they should not be flipped
+    pub fn create_cube_map<const BI: usize>(bn: &mut TextureBouncer<BI>, faces: [&FloatImage; 6]) -> Result<UnboundTexture<2, { gl::TEXTURE_CUBE_MAP }>, String> {
+        let channels = faces[0].channels;
+        if faces.iter().any(|face| face.channels != channels) {
+            return Err("Cube map faces must all have the same number of channels!".to_owned());
+        }
+        let data: Vec<UploadData> = faces.iter().map(|face| Self::to_upload_data(channels, &face.data)).collect();
+        Self::upload_cube_faces(bn, std::array::from_fn(|i| faces[i].size), &data)
+    }
+
+    fn upload_cube_faces<const BI: usize>(
+        bn: &mut TextureBouncer<BI>,
+        sizes: [[usize; 2]; 6],
+        data: &[UploadData],
+    ) -> Result<UnboundTexture<2, { gl::TEXTURE_CUBE_MAP }>, String> {
+        match &data[0] {
+            UploadData::Rgb(_) => {
+                let faces: Vec<&[Half]> = data.iter().filter_map(|face| if let UploadData::Rgb(face) = face { Some(face.as_slice()) } else { None }).collect();
+                TextureCube::with_faces(bn, std::array::from_fn(|i| (sizes[i], faces[i])), gl::RGB)
+            }
+            UploadData::Rgba(_) => {
+                let faces: Vec<&[f32]> = data.iter().filter_map(|face| if let UploadData::Rgba(face) = face { Some(face.as_slice()) } else { None }).collect();
+                TextureCube::with_faces(bn, std::array::from_fn(|i| (sizes[i], faces[i])), gl::RGBA)
+            }
+        }
+    }
+
+    /// The direction from the center of the cube through a point of a face, s and t go from -1 to 1 along the rows and columns of the face as opengl stores them
+    fn get_cube_direction(face: CubeFace, s: f32, t: f32) -> [f32; 3] {
+        match face {
+            CubeFace::PositiveX => [1.0, -t, -s],
+            CubeFace::NegativeX => [-1.0, -t, s],
+            CubeFace::PositiveY => [s, 1.0, t],
+            CubeFace::NegativeY => [s, -1.0, -t],
+            CubeFace::PositiveZ => [s, -t, 1.0],
+            CubeFace::NegativeZ => [-s, -t, -1.0],
+        }
+    }
+
+    /// Bilinearly samples the image at u, v ( from 0 to 1, v = 0 being the top row ), wrapping around horizontally
+    fn sample(&self, u: f32, v: f32, out: &mut [f32]) {
+        let [w, h] = self.size;
+        let channels = usize::from(self.channels);
+        let x = u * w as f32 - 0.5;
+        let y = (v * h as f32 - 0.5).clamp(0.0, (h - 1) as f32);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let x0 = (x0 as isize).rem_euclid(w as isize) as usize;
+        let x1 = (x0 + 1) % w;
+        let y0 = y0 as usize;
+        let y1 = (y0 + 1).min(h - 1);
+        let texel = |x: usize, y: usize, c: usize| self.data[(y * w + x) * channels + c];
+        for (c, out) in out.iter_mut().enumerate() {
+            let top = texel(x0, y0, c) * (1.0 - fx) + texel(x1, y0, c) * fx;
+            let bottom = texel(x0, y1, c) * (1.0 - fx) + texel(x1, y1, c) * fx;
+            *out = top * (1.0 - fy) + bottom * fy;
+        }
+    }
+
+    /// Creates a cube map with faces of face_size pixels from an equirectangular ( latitude-longitude ) panorama, which is how most hdr environment maps come
+    /// The center of the panorama ends up facing -Z and the top row is straight up ( +Y )
+    pub fn create_cube_map_from_equirectangular<const BI: usize>(
+        &self,
+        bn: &mut TextureBouncer<BI>,
+        face_size: usize,
+    ) -> Result<UnboundTexture<2, { gl::TEXTURE_CUBE_MAP }>, String> {
+        if face_size == 0 {
+            return Err("Cube map faces can't be empty!".to_owned());
+        }
+        let channels = usize::from(self.channels);
+        let data: Vec<UploadData> = CubeFace::ALL
+            .iter()
+            .map(|face| {
+                let mut face_data = vec![0.0; face_size * face_size * channels];
+                for (i, pixel) in face_data.chunks_exact_mut(channels).enumerate() {
+                    let s = 2.0 * ((i % face_size) as f32 + 0.5) / face_size as f32 - 1.0;
+                    let t = 2.0 * ((i / face_size) as f32 + 0.5) / face_size as f32 - 1.0;
+                    let [x, y, z] = Self::get_cube_direction(*face, s, t);
+                    let len = (x * x + y * y + z * z).sqrt();
+                    let u = 0.5 + x.atan2(-z) / (2.0 * PI);
+                    let v = 0.5 - (y / len).asin() / PI;
+                    self.sample(u, v, pixel);
+                }
+                Self::to_upload_data(self.channels, &face_data)
+            })
+            .collect();
+        Self::upload_cube_faces(bn, [[face_size; 2]; 6], &data)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::render::texture::{Swizzle, Texture2D, TextureBouncer, UnboundTexture};
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret, HasGLEnum};
+use gl::types::*;
+use image::DynamicImage;
+use std::borrow::Cow;
+use std::convert::TryInto;
+
+/// Grayscale images only have one ( or two with alpha ) channels, so they are swizzled to read as gray instead of red
+fn get_swizzle_of(img: &DynamicImage) -> Option<[Swizzle; 4]> {
+    use Swizzle::*;
+    match img {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => Some([Red, Red, Red, One]),
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLumaA16(_) => Some([Red, Red, Red, Green]),
+        _ => None,
+    }
+}
+
+/// Returns the format of the data for the number of channels of the image
+fn get_format_of(img: &DynamicImage) -> Option<GLenum> {
+    Some(match img.color().channel_count() {
+        1 => gl::RED,
+        2 => gl::RG,
+        3 => gl::RGB,
+        4 => gl::RGBA,
+        _ => return None,
+    })
+}
+
+fn create_texture<ET, const BI: usize>(
+    bn: &mut TextureBouncer<BI>,
+    size: [usize; 2],
+    data: &[ET],
+    format: GLenum,
+    swizzle: Option<[Swizzle; 4]>,
+    generate_mipmaps: bool,
+) -> Result<UnboundTexture<2, { gl::TEXTURE_2D }>, String>
+where
+    ET: HasGLEnum,
+{
+    let (internal_fmt, _) = unwrap_option_or_ret!(
+        crate::format_to_gl_internal_format(ET::get_gl_type(), format),
+        Err(format!("Unsupported format type combination: {:#x} {:#x}!", ET::get_gl_type(), format))
+    );
+    let internal_format: GLenum = unwrap_result_or_ret!(internal_fmt.try_into(), Err("Converting to gl types!".to_owned()));
+    let levels = if generate_mipmaps {
+        (usize::BITS - size[0].max(size[1]).leading_zeros()) as usize
+    } else {
+        1
+    };
+    let mut r = Texture2D::with_immutable_storage(bn, levels, internal_format, size)?;
+    {
+        let mut r = r.bind_mut(bn);
+        r.update_region([0, 0], size, data)?;
+        if let Some(swizzle) = swizzle {
+            r.set_swizzle_of_bound_tex(swizzle)?;
+        }
+        if levels > 1 {
+            r.generate_mipmaps_of_bound_tex()?;
+            r.set_min_filter_of_bound_tex(gl::LINEAR_MIPMAP_LINEAR)?;
+        }
+    }
+    Ok(r)
+}
+
+impl Texture2D {
+    /// Creates a texture with the size, channels and element type of the image ( 8 bit, 16 bit or 32 bit float ), grayscale images are swizzled to read as gray
+    /// Images are stored top row first while opengl expects the bottom row first, so flip_vertically should be true unless the texture coordinates account for that
+    pub fn from_image<const BI: usize>(
+        bn: &mut TextureBouncer<BI>,
+        img: &DynamicImage,
+        flip_vertically: bool,
+        generate_mipmaps: bool,
+    ) -> Result<UnboundTexture<2, { gl::TEXTURE_2D }>, String> {
+        let img = if flip_vertically { Cow::Owned(img.flipv()) } else { Cow::Borrowed(img) };
+        let size: [usize; 2] = [
+            unwrap_result_or_ret!(img.width().try_into(), Err("Image width too big!".to_owned())),
+            unwrap_result_or_ret!(img.height().try_into(), Err("Image height too big!".to_owned())),
+        ];
+        let format = unwrap_option_or_ret!(get_format_of(&img), Err("Unsupported number of channels in image!".to_owned()));
+        let swizzle = get_swizzle_of(&img);
+        match img.as_ref() {
+            DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_) | DynamicImage::ImageRgb8(_) | DynamicImage::ImageRgba8(_) => {
+                create_texture::<GLubyte, BI>(bn, size, img.as_bytes(), format, swizzle, generate_mipmaps)
+            }
+            DynamicImage::ImageLuma16(buf) => create_texture::<GLushort, BI>(bn, size, buf.as_raw(), format, swizzle, generate_mipmaps),
+            DynamicImage::ImageLumaA16(buf) => create_texture::<GLushort, BI>(bn, size, buf.as_raw(), format, swizzle, generate_mipmaps),
+            DynamicImage::ImageRgb16(buf) => create_texture::<GLushort, BI>(bn, size, buf.as_raw(), format, swizzle, generate_mipmaps),
+            DynamicImage::ImageRgba16(buf) => create_texture::<GLushort, BI>(bn, size, buf.as_raw(), format, swizzle, generate_mipmaps),
+            DynamicImage::ImageRgb32F(buf) => create_texture::<GLfloat, BI>(bn, size, buf.as_raw(), format, swizzle, generate_mipmaps),
+            DynamicImage::ImageRgba32F(buf) => create_texture::<GLfloat, BI>(bn, size, buf.as_raw(), format, swizzle, generate_mipmaps),
+            _ => Err(format!("Unsupported image color type: {:?}!", img.color())),
+        }
+    }
+}
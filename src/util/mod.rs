@@ -1,2 +1,9 @@
 pub mod aggregator_obj;
 pub mod buffer_obj;
+#[cfg(feature = "image")]
+pub mod hdr_image;
+#[cfg(feature = "image")]
+pub mod image_texture;
+pub mod pixel_unpack_ring;
+pub mod texture_atlas;
+pub mod texture_container;
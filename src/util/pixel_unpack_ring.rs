@@ -0,0 +1,165 @@
+use crate::render::texture::Texture;
+use crate::util::buffer_obj::BOBase;
+use crate::{unwrap_option_or_ret, HasGLEnum};
+use gl::types::*;
+use std::convert::TryInto;
+use std::mem::size_of;
+
+/// The number of buffers in a ring, one being written by the cpu while the gpu can still be reading the other two
+pub const NUM_OF_UNPACK_BUFFERS: usize = 3;
+
+/// How long to wait for a fence at a time, waiting is retried until the fence is signaled so this only limits how long a single call blocks
+const FENCE_WAIT_TIMEOUT_NS: GLuint64 = 1_000_000_000;
+
+/// Streams texture uploads through a ring of pixel unpack buffers, for data that changes every frame ( like video frames )
+/// The next frame is written straight into mapped buffer memory and the upload from it runs asynchronously, so the cpu doesn't stall until the gpu is done reading it
+/// A fence is put after every upload and waited for before its buffer is written again, so a buffer that the gpu is still reading is never overwritten
+/// NOTE: Fences need opengl 3.2 ( or ARB_sync ), new returns an error without them
+pub struct PixelUnpackRing<ET> {
+    buffers: [BOBase<ET>; NUM_OF_UNPACK_BUFFERS],
+    /// The fence put after the last upload from every buffer, None if it hasn't been used yet or the fence has been waited for
+    fences: [Option<GLsync>; NUM_OF_UNPACK_BUFFERS],
+    /// The buffer the next upload is written to
+    next: usize,
+    /// The number of elements in every buffer
+    len: usize,
+}
+
+impl<ET> Drop for PixelUnpackRing<ET> {
+    fn drop(&mut self) {
+        for fence in self.fences.iter_mut().filter_map(Option::take) {
+            unsafe {
+                gl::DeleteSync(fence);
+            }
+        }
+    }
+}
+
+impl<ET> PixelUnpackRing<ET>
+where
+    ET: HasGLEnum,
+{
+    /// Every buffer holds len elements, which has to be enough for the biggest region that is going to be uploaded ( like width * height * 4 for RGBA frames )
+    pub fn new(len: usize) -> Result<Self, String> {
+        if crate::get_gl_version() < (3, 2) && !crate::is_gl_extension_supported("GL_ARB_sync") {
+            return Err("Pixel unpack rings need fences, which need opengl 3.2 or ARB_sync!".to_owned());
+        }
+        if len == 0 {
+            return Err("Pixel unpack buffers can't be empty!".to_owned());
+        }
+        let mut buffers: [BOBase<ET>; NUM_OF_UNPACK_BUFFERS] = std::array::from_fn(|_| BOBase::new());
+        for buffer in &mut buffers {
+            unsafe {
+                gl::BindBuffer(gl::PIXEL_UNPACK_BUFFER, buffer.get_id());
+            }
+            let res = buffer.allocate(gl::PIXEL_UNPACK_BUFFER, len, gl::STREAM_DRAW);
+            unsafe {
+                gl::BindBuffer(gl::PIXEL_UNPACK_BUFFER, 0);
+            }
+            res?;
+        }
+        Ok(PixelUnpackRing {
+            buffers,
+            fences: [None; NUM_OF_UNPACK_BUFFERS],
+            next: 0,
+            len,
+        })
+    }
+
+    /// The number of elements in every buffer
+    #[inline(always)]
+    pub fn get_len(&self) -> usize {
+        self.len
+    }
+
+    /// False if the gpu is still reading the next buffer, so stream_region would block until it's done
+    /// Useful to drop a frame instead of stalling when the gpu falls behind
+    pub fn is_next_buffer_free(&self) -> bool {
+        match self.fences[self.next] {
+            Some(fence) => matches!(unsafe { gl::ClientWaitSync(fence, 0, 0) }, gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED),
+            None => true,
+        }
+    }
+
+    /// Blocks until the gpu is done reading the next buffer
+    fn wait_for_next_buffer(&mut self) -> Result<(), String> {
+        let fence = unwrap_option_or_ret!(self.fences[self.next].take(), Ok(()));
+        let res = loop {
+            match unsafe { gl::ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, FENCE_WAIT_TIMEOUT_NS) } {
+                gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => break Ok(()),
+                gl::TIMEOUT_EXPIRED => continue,
+                _ => break Err("Waiting for the gpu to finish reading a pixel unpack buffer failed!".to_owned()),
+            }
+        };
+        unsafe {
+            gl::DeleteSync(fence);
+        }
+        res
+    }
+
+    /// Maps the next buffer, lets write fill the region with tightly packed data in the format the texture was allocated with and updates the region of the mip level from it
+    /// The texture is updated asynchronously, this only blocks if the gpu is still reading the buffer from NUM_OF_UNPACK_BUFFERS uploads ago
+    /// NOTE: The slice write gets has exactly as many elements as the region needs and its contents are undefined, so every element should be written
+    /// write can do other opengl work, but if it binds a texture through another bouncer the upload returns an error instead of going to whatever ends up bound on the active unit
+    pub fn stream_region<F, const N: usize, const TYP: GLenum>(
+        &mut self,
+        tex: &mut Texture<N, TYP>,
+        level: usize,
+        offset: [usize; N],
+        size: [usize; N],
+        write: F,
+    ) -> Result<(), String>
+    where
+        F: FnOnce(&mut [ET]),
+    {
+        // NOTE: Everything is checked before waiting for and writing to the buffer, so a region that can't be uploaded never costs a stall or a call to write
+        let (_, epp) = tex.validate_unpack_buffer_update::<ET>(level, offset, size)?;
+        let len = unwrap_option_or_ret!(
+            size.iter().try_fold(usize::from(epp), |acc, s| acc.checked_mul(*s)),
+            Err(format!("Region of size {:?} is too big!", size))
+        );
+        if len > self.len {
+            return Err(format!("A region of size {:?} needs {} values but the pixel unpack buffers only hold {}!", size, len, self.len));
+        }
+        // NOTE: Fits since the buffers are at least this big
+        let map_len: GLsizeiptr = (len * size_of::<ET>()).try_into().expect("Converting to gl types!");
+        self.wait_for_next_buffer()?;
+
+        let buffer_id = self.buffers[self.next].get_id();
+        let bind = |id: GLuint| unsafe { gl::BindBuffer(gl::PIXEL_UNPACK_BUFFER, id) };
+        // NOTE: The buffer is only bound while it's mapped, unmapped and read from, since while it's bound every other texture upload ( maybe done by write ) would read from it
+        // Unsynchronized is safe since the fence of the buffer has already been waited for, and invalidating lets the driver skip keeping the old contents around
+        bind(buffer_id);
+        let ptr = unsafe {
+            gl::MapBufferRange(
+                gl::PIXEL_UNPACK_BUFFER,
+                0,
+                map_len,
+                gl::MAP_WRITE_BIT | gl::MAP_INVALIDATE_BUFFER_BIT | gl::MAP_UNSYNCHRONIZED_BIT,
+            )
+        };
+        bind(0);
+        if ptr.is_null() {
+            return Err("Opengl could not map the pixel unpack buffer!".to_owned());
+        }
+        write(unsafe { std::slice::from_raw_parts_mut(ptr as *mut ET, len) });
+
+        bind(buffer_id);
+        let res = if unsafe { gl::UnmapBuffer(gl::PIXEL_UNPACK_BUFFER) } == gl::FALSE {
+            Err("Contents of the pixel unpack buffer were lost while it was mapped, the region has to be streamed again!".to_owned())
+        } else {
+            // NOTE: This checks again that tex is still bound to the active unit, write may have made another unit active since the check above
+            tex.update_region_from_unpack_buffer::<ET>(level, offset, size, 0, self.len)
+        };
+        bind(0);
+        res?;
+
+        let fence = unsafe { gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) };
+        if fence.is_null() {
+            return Err("Opengl could not create a fence for the upload!".to_owned());
+        }
+        self.fences[self.next] = Some(fence);
+        self.next = (self.next + 1) % NUM_OF_UNPACK_BUFFERS;
+        Ok(())
+    }
+}
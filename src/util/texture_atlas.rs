@@ -0,0 +1,603 @@
+use crate::render::texture::{Texture2D, Texture2DArr, TextureBouncer, UnboundTexture};
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret, HasGLEnum};
+use gl::types::*;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Where an image ended up in an atlas, the page is the index of the texture ( or the layer of the texture array ) it is in
+/// NOTE: The first row of the image is at uv_min[1], so images stored top row first end up upside down unless the texture coordinates account for that
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasRegion {
+    page: usize,
+    offset: [usize; 2],
+    size: [usize; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+}
+
+impl AtlasRegion {
+    #[inline(always)]
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
+    /// The offset of the image in the page in pixels, without the extruded edges
+    #[inline(always)]
+    pub fn get_offset(&self) -> [usize; 2] {
+        self.offset
+    }
+
+    #[inline(always)]
+    pub fn get_size(&self) -> [usize; 2] {
+        self.size
+    }
+
+    #[inline(always)]
+    pub fn get_uv_min(&self) -> [f32; 2] {
+        self.uv_min
+    }
+
+    #[inline(always)]
+    pub fn get_uv_max(&self) -> [f32; 2] {
+        self.uv_max
+    }
+}
+
+/// Packs rectangles into a page by keeping track of the top edge ( the skyline ) of everything packed so far and putting every rectangle as low as it goes
+struct Skyline {
+    size: [usize; 2],
+    /// x, y and width of every segment of the skyline, from left to right with no gaps
+    segments: Vec<[usize; 3]>,
+}
+
+impl Skyline {
+    fn new(size: [usize; 2]) -> Self {
+        Skyline {
+            size,
+            segments: vec![[0, 0, size[0]]],
+        }
+    }
+
+    /// Returns how high a rectangle that starts at segment i has to be put so it lies on top of every segment under it
+    fn fit(&self, i: usize, size: [usize; 2]) -> Option<usize> {
+        let [w, h] = size;
+        if self.segments[i][0] + w > self.size[0] {
+            return None;
+        }
+        let mut y = 0;
+        let mut left = w;
+        for seg in &self.segments[i..] {
+            y = y.max(seg[1]);
+            if y + h > self.size[1] {
+                return None;
+            }
+            if seg[2] >= left {
+                break;
+            }
+            left -= seg[2];
+        }
+        Some(y)
+    }
+
+    /// Returns the segment the lowest spot for the rectangle starts at and how high it is, without packing it
+    fn find(&self, size: [usize; 2]) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        for i in 0..self.segments.len() {
+            if let Some(y) = self.fit(i, size) {
+                if best.is_none_or(|(_, best_y)| y < best_y) {
+                    best = Some((i, y));
+                }
+            }
+        }
+        best
+    }
+
+    /// Packs the rectangle at the spot find returned and returns its position
+    fn commit(&mut self, spot: (usize, usize), size: [usize; 2]) -> [usize; 2] {
+        let (i, y) = spot;
+        let x = self.segments[i][0];
+        let end = x + size[0];
+        self.segments.insert(i, [x, y + size[1], size[0]]);
+        // Cut away the parts of the segments the rectangle now covers
+        let j = i + 1;
+        while j < self.segments.len() && self.segments[j][0] < end {
+            let [sx, sy, sw] = self.segments[j];
+            if sx + sw <= end {
+                self.segments.remove(j);
+            } else {
+                self.segments[j] = [end, sy, sx + sw - end];
+                break;
+            }
+        }
+        // Neighbours at the same height are merged so wide rectangles can be put on top of both
+        let mut j = 0;
+        while j + 1 < self.segments.len() {
+            if self.segments[j][1] == self.segments[j + 1][1] {
+                self.segments[j][2] += self.segments[j + 1][2];
+                self.segments.remove(j + 1);
+            } else {
+                j += 1;
+            }
+        }
+        [x, y]
+    }
+}
+
+/// Repeats the edge pixels of the image extrusion times on every side, so linear filtering at the edges doesn't blend in the neighbouring images
+fn extrude<ET: Copy>(size: [usize; 2], data: &[ET], epp: usize, extrusion: usize) -> Vec<ET> {
+    let extruded_size = [size[0] + 2 * extrusion, size[1] + 2 * extrusion];
+    let mut r = Vec::with_capacity(extruded_size[0] * extruded_size[1] * epp);
+    for y in 0..extruded_size[1] {
+        let src_y = y.saturating_sub(extrusion).min(size[1] - 1);
+        for x in 0..extruded_size[0] {
+            let src_x = x.saturating_sub(extrusion).min(size[0] - 1);
+            let start = (src_y * size[0] + src_x) * epp;
+            r.extend_from_slice(&data[start..start + epp]);
+        }
+    }
+    r
+}
+
+/// What has to be put back to undo placing an image, for when it can't be uploaded
+struct PlacementUndo {
+    num_of_pages: usize,
+    page: usize,
+    segments: Vec<[usize; 3]>,
+}
+
+/// What both kinds of atlases need to place images, the textures are left to them
+struct AtlasLayout<K, ET> {
+    page_size: [usize; 2],
+    padding: usize,
+    extrusion: usize,
+    format: GLenum,
+    internal_format: GLenum,
+    epp: usize,
+    pages: Vec<Skyline>,
+    regions: HashMap<K, AtlasRegion>,
+    data: PhantomData<ET>,
+}
+
+impl<K, ET> AtlasLayout<K, ET>
+where
+    K: Eq + Hash,
+    ET: HasGLEnum + Copy + Default,
+{
+    fn new(page_size: [usize; 2], format: GLenum) -> Result<Self, String> {
+        if page_size.contains(&0) {
+            return Err("Atlas pages can't be empty!".to_owned());
+        }
+        let (internal_fmt, epp) = unwrap_option_or_ret!(
+            crate::format_to_gl_internal_format(ET::get_gl_type(), format),
+            Err(format!("Unsupported format type combination: {:#x} {:#x}!", ET::get_gl_type(), format))
+        );
+        let internal_format: GLenum = unwrap_result_or_ret!(internal_fmt.try_into(), Err("Converting to gl types!".to_owned()));
+        Ok(AtlasLayout {
+            page_size,
+            padding: 0,
+            extrusion: 0,
+            format,
+            internal_format,
+            epp: usize::from(epp),
+            pages: Vec::new(),
+            regions: HashMap::new(),
+            data: PhantomData,
+        })
+    }
+
+    /// The size the image takes up in a page with its extruded edges and padding
+    fn get_slot_size(&self, size: [usize; 2], data: &[ET]) -> Result<[usize; 2], String> {
+        if size.contains(&0) {
+            return Err("Images in an atlas can't be empty!".to_owned());
+        }
+        let expected_len = size[0].checked_mul(size[1]).and_then(|len| len.checked_mul(self.epp));
+        if expected_len != Some(data.len()) {
+            return Err(format!("Image of size {:?} with {} values per pixel does not have {} values!", size, self.epp, data.len()));
+        }
+        let slot_size = [
+            size[0].saturating_add(2 * self.extrusion + self.padding),
+            size[1].saturating_add(2 * self.extrusion + self.padding),
+        ];
+        // NOTE: Padding is only needed between images, so the last one in a row or column can have its padding hang off the page
+        if slot_size[0] > self.page_size[0] + self.padding || slot_size[1] > self.page_size[1] + self.padding {
+            return Err(format!("Image of size {:?} with its extruded edges does not fit in a page of size {:?}!", size, self.page_size));
+        }
+        Ok(slot_size)
+    }
+
+    /// Finds room for the image in the existing pages or in a new one if there are less than max_pages, returns its region, its data with the edges extruded and how to undo placing it
+    /// NOTE: The region isn't recorded under key, that's left for once the image has been uploaded
+    fn place(&mut self, key: &K, size: [usize; 2], data: &[ET], max_pages: usize) -> Result<(AtlasRegion, Vec<ET>, PlacementUndo), String> {
+        if self.regions.contains_key(key) {
+            return Err("There already is an image with the same key in the atlas!".to_owned());
+        }
+        let slot_size = self.get_slot_size(size, data)?;
+        let num_of_pages = self.pages.len();
+        let mut placement = self.pages.iter().enumerate().find_map(|(i, page)| Some((i, page.find(slot_size)?)));
+        if placement.is_none() && num_of_pages < max_pages {
+            let page = Skyline::new([self.page_size[0] + self.padding, self.page_size[1] + self.padding]);
+            placement = page.find(slot_size).map(|spot| (num_of_pages, spot));
+            self.pages.push(page);
+        }
+        let (page, spot) = match placement {
+            Some(placement) => placement,
+            None => {
+                self.pages.truncate(num_of_pages);
+                return Err(format!("No room left in the atlas for an image of size {:?}!", size));
+            }
+        };
+        let undo = PlacementUndo {
+            num_of_pages,
+            page,
+            segments: self.pages[page].segments.clone(),
+        };
+        let pos = self.pages[page].commit(spot, slot_size);
+        let offset = [pos[0] + self.extrusion, pos[1] + self.extrusion];
+        let region = AtlasRegion {
+            page,
+            offset,
+            size,
+            uv_min: [offset[0] as f32 / self.page_size[0] as f32, offset[1] as f32 / self.page_size[1] as f32],
+            uv_max: [
+                (offset[0] + size[0]) as f32 / self.page_size[0] as f32,
+                (offset[1] + size[1]) as f32 / self.page_size[1] as f32,
+            ],
+        };
+        Ok((region, extrude(size, data, self.epp, self.extrusion), undo))
+    }
+
+    fn undo(&mut self, undo: PlacementUndo) {
+        if let Some(page) = self.pages.get_mut(undo.page) {
+            page.segments = undo.segments;
+        }
+        self.pages.truncate(undo.num_of_pages);
+    }
+
+    /// The position and size of the image with its extruded edges
+    #[inline(always)]
+    fn get_extruded_rect(&self, region: &AtlasRegion) -> ([usize; 2], [usize; 2]) {
+        (
+            [region.offset[0] - self.extrusion, region.offset[1] - self.extrusion],
+            [region.size[0] + 2 * self.extrusion, region.size[1] + 2 * self.extrusion],
+        )
+    }
+
+    /// Zeroed data for a whole page, so the padding between images is transparent
+    fn get_clear_data(&self) -> Vec<ET> {
+        vec![ET::default(); self.page_size[0] * self.page_size[1] * self.epp]
+    }
+}
+
+/// Collects images to pack them all at once into an atlas, packing them together gives tighter pages than adding them one at a time
+pub struct TextureAtlasBuilder<'a, K, ET> {
+    layout: AtlasLayout<K, ET>,
+    images: Vec<(K, [usize; 2], &'a [ET])>,
+}
+
+impl<'a, K, ET> TextureAtlasBuilder<'a, K, ET>
+where
+    K: Eq + Hash,
+    ET: HasGLEnum + Copy + Default,
+{
+    /// Every page of the atlas has page_size pixels, the images are laid out as format ( like gl::RGBA ) with elements of ET
+    pub fn new(page_size: [usize; 2], format: GLenum) -> Result<Self, String> {
+        Ok(TextureAtlasBuilder {
+            layout: AtlasLayout::new(page_size, format)?,
+            images: Vec::new(),
+        })
+    }
+
+    /// The number of empty pixels between images
+    #[inline(always)]
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.layout.padding = padding;
+        self
+    }
+
+    /// The number of times the edge pixels of every image are repeated around it
+    #[inline(always)]
+    pub fn with_extrusion(mut self, extrusion: usize) -> Self {
+        self.layout.extrusion = extrusion;
+        self
+    }
+
+    /// The data is laid out row by row with no padding between rows, images with the same key are only caught once the atlas is built
+    pub fn add_image(&mut self, key: K, size: [usize; 2], data: &'a [ET]) -> Result<(), String> {
+        self.layout.get_slot_size(size, data)?;
+        self.images.push((key, size, data));
+        Ok(())
+    }
+
+    /// Places the images tallest first and returns their regions and data in the order they were added
+    fn place_all(&mut self, max_pages: usize) -> Result<Vec<(AtlasRegion, Vec<ET>)>, String> {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|i| std::cmp::Reverse((self.images[*i].1[1], self.images[*i].1[0])));
+        let mut placed = Vec::with_capacity(self.images.len());
+        let mut images: Vec<Option<_>> = self.images.drain(..).map(Some).collect();
+        for i in order {
+            let (key, size, data) = images[i].take().expect("Every image is placed once!");
+            let (region, data, _) = self.layout.place(&key, size, data, max_pages)?;
+            self.layout.regions.insert(key, region);
+            placed.push((i, (region, data)));
+        }
+        placed.sort_by_key(|(i, _)| *i);
+        Ok(placed.into_iter().map(|(_, placed)| placed).collect())
+    }
+
+    /// Packs the images into as many 2D textures as needed
+    pub fn build<const BI: usize>(mut self, bn: &mut TextureBouncer<BI>) -> Result<TextureAtlas<K, ET>, String> {
+        let placed = self.place_all(usize::MAX)?;
+        let mut atlas = TextureAtlas {
+            layout: self.layout,
+            pages: Vec::new(),
+        };
+        while atlas.pages.len() < atlas.layout.pages.len() {
+            atlas.add_page(bn)?;
+        }
+        for (region, data) in placed {
+            atlas.upload(bn, &region, &data)?;
+        }
+        Ok(atlas)
+    }
+
+    /// Packs the images into the layers of a 2D texture array, the layers that aren't needed are left empty for images added later
+    pub fn build_array<const BI: usize>(mut self, bn: &mut TextureBouncer<BI>, layers: usize) -> Result<TextureArrayAtlas<K, ET>, String> {
+        if layers == 0 {
+            return Err("Atlas texture arrays need at least one layer!".to_owned());
+        }
+        let placed = self.place_all(layers)?;
+        let layout = self.layout;
+        let mut texture = Texture2DArr::with_immutable_storage(bn, 1, layout.internal_format, [layout.page_size[0], layout.page_size[1], layers])?;
+        {
+            let mut texture = texture.bind_mut(bn);
+            let clear_data = layout.get_clear_data();
+            for layer in 0..layers {
+                texture.update_region([0, 0, layer], [layout.page_size[0], layout.page_size[1], 1], &clear_data)?;
+            }
+        }
+        let mut atlas = TextureArrayAtlas { layout, texture, layers };
+        for (region, data) in placed {
+            atlas.upload(bn, &region, &data)?;
+        }
+        Ok(atlas)
+    }
+}
+
+/// Images packed into pages of 2D textures, more pages are added as images that don't fit are added
+pub struct TextureAtlas<K, ET> {
+    layout: AtlasLayout<K, ET>,
+    pages: Vec<UnboundTexture<2, { gl::TEXTURE_2D }>>,
+}
+
+impl<K, ET> TextureAtlas<K, ET>
+where
+    K: Eq + Hash,
+    ET: HasGLEnum + Copy + Default,
+{
+    fn add_page<const BI: usize>(&mut self, bn: &mut TextureBouncer<BI>) -> Result<(), String> {
+        let mut page = Texture2D::with_immutable_storage(bn, 1, self.layout.internal_format, self.layout.page_size)?;
+        page.bind_mut(bn).update_region([0, 0], self.layout.page_size, &self.layout.get_clear_data())?;
+        self.pages.push(page);
+        Ok(())
+    }
+
+    fn upload<const BI: usize>(&mut self, bn: &mut TextureBouncer<BI>, region: &AtlasRegion, data: &[ET]) -> Result<(), String> {
+        let (offset, size) = self.layout.get_extruded_rect(region);
+        self.pages[region.page].bind_mut(bn).update_region(offset, size, data)
+    }
+
+    /// Packs the image into the existing pages with a sub-image update, only if it doesn't fit in any of them a new page is created
+    pub fn add_image<const BI: usize>(&mut self, bn: &mut TextureBouncer<BI>, key: K, size: [usize; 2], data: &[ET]) -> Result<AtlasRegion, String> {
+        let (region, data, undo) = self.layout.place(&key, size, data, usize::MAX)?;
+        // NOTE: A page whose texture was created before an upload failed is reused, so there can be more textures than pages in the layout
+        let res = if region.page >= self.pages.len() { self.add_page(bn) } else { Ok(()) };
+        if let Err(e) = res.and_then(|_| self.upload(bn, &region, &data)) {
+            self.layout.undo(undo);
+            return Err(e);
+        }
+        self.layout.regions.insert(key, region);
+        Ok(region)
+    }
+
+    #[inline(always)]
+    pub fn get_region(&self, key: &K) -> Option<&AtlasRegion> {
+        self.layout.regions.get(key)
+    }
+
+    #[inline(always)]
+    pub fn get_regions(&self) -> &HashMap<K, AtlasRegion> {
+        &self.layout.regions
+    }
+
+    /// The texture of every page, AtlasRegion::get_page is an index into this
+    #[inline(always)]
+    pub fn get_pages(&self) -> &[UnboundTexture<2, { gl::TEXTURE_2D }>] {
+        &self.pages
+    }
+
+    #[inline(always)]
+    pub fn get_page_size(&self) -> [usize; 2] {
+        self.layout.page_size
+    }
+
+    #[inline(always)]
+    pub fn get_format(&self) -> GLenum {
+        self.layout.format
+    }
+}
+
+/// Images packed into the layers of a 2D texture array, so all of them can be drawn with one texture bound
+/// NOTE: The number of layers is fixed when the atlas is built, images that don't fit in any layer can't be added later
+pub struct TextureArrayAtlas<K, ET> {
+    layout: AtlasLayout<K, ET>,
+    texture: UnboundTexture<3, { gl::TEXTURE_2D_ARRAY }>,
+    layers: usize,
+}
+
+impl<K, ET> TextureArrayAtlas<K, ET>
+where
+    K: Eq + Hash,
+    ET: HasGLEnum + Copy + Default,
+{
+    fn upload<const BI: usize>(&mut self, bn: &mut TextureBouncer<BI>, region: &AtlasRegion, data: &[ET]) -> Result<(), String> {
+        let (offset, size) = self.layout.get_extruded_rect(region);
+        self.texture
+            .bind_mut(bn)
+            .update_region([offset[0], offset[1], region.page], [size[0], size[1], 1], data)
+    }
+
+    /// Packs the image into a layer that has room for it with a sub-image update
+    pub fn add_image<const BI: usize>(&mut self, bn: &mut TextureBouncer<BI>, key: K, size: [usize; 2], data: &[ET]) -> Result<AtlasRegion, String> {
+        let (region, data, undo) = self.layout.place(&key, size, data, self.layers)?;
+        if let Err(e) = self.upload(bn, &region, &data) {
+            self.layout.undo(undo);
+            return Err(e);
+        }
+        self.layout.regions.insert(key, region);
+        Ok(region)
+    }
+
+    #[inline(always)]
+    pub fn get_region(&self, key: &K) -> Option<&AtlasRegion> {
+        self.layout.regions.get(key)
+    }
+
+    #[inline(always)]
+    pub fn get_regions(&self) -> &HashMap<K, AtlasRegion> {
+        &self.layout.regions
+    }
+
+    /// AtlasRegion::get_page is the layer of this texture the image is in
+    #[inline(always)]
+    pub fn get_texture(&self) -> &UnboundTexture<3, { gl::TEXTURE_2D_ARRAY }> {
+        &self.texture
+    }
+
+    #[inline(always)]
+    pub fn get_num_of_layers(&self) -> usize {
+        self.layers
+    }
+
+    #[inline(always)]
+    pub fn get_page_size(&self) -> [usize; 2] {
+        self.layout.page_size
+    }
+
+    #[inline(always)]
+    pub fn get_format(&self) -> GLenum {
+        self.layout.format
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(page_size: [usize; 2], padding: usize, extrusion: usize) -> AtlasLayout<u32, u8> {
+        let mut r = AtlasLayout::new(page_size, gl::RED).unwrap();
+        r.padding = padding;
+        r.extrusion = extrusion;
+        r
+    }
+
+    /// Places the image and records it like the atlases do once it has been uploaded
+    fn place(layout: &mut AtlasLayout<u32, u8>, key: u32, size: [usize; 2], max_pages: usize) -> Result<AtlasRegion, String> {
+        let (region, data, _) = layout.place(&key, size, &vec![0; size[0] * size[1]], max_pages)?;
+        assert_eq!(data.len(), (size[0] + 2 * layout.extrusion) * (size[1] + 2 * layout.extrusion));
+        layout.regions.insert(key, region);
+        Ok(region)
+    }
+
+    #[test]
+    fn regions_with_padding_and_extrusion_do_not_overlap() {
+        let mut layout = layout([64, 64], 2, 1);
+        for key in 0..40 {
+            place(&mut layout, key, [1 + (key as usize * 7) % 11, 1 + (key as usize * 5) % 9], usize::MAX).unwrap();
+        }
+        let rects: Vec<_> = layout.regions.values().map(|region| (region.page, layout.get_extruded_rect(region))).collect();
+        for (i, (page, (pos, size))) in rects.iter().enumerate() {
+            assert!(pos[0] + size[0] <= 64 && pos[1] + size[1] <= 64);
+            for (other_page, (other_pos, other_size)) in &rects[i + 1..] {
+                // NOTE: Padding is added to both rects, so there has to be at least that much room between them
+                let apart = (0..2).any(|d| pos[d] + size[d] + 2 <= other_pos[d] || other_pos[d] + other_size[d] + 2 <= pos[d]);
+                assert!(page != other_page || apart, "{:?} {:?} overlap", (pos, size), (other_pos, other_size));
+            }
+        }
+    }
+
+    #[test]
+    fn spills_over_to_a_new_page() {
+        let mut layout = layout([8, 8], 0, 0);
+        assert_eq!(place(&mut layout, 0, [8, 4], usize::MAX).unwrap().get_page(), 0);
+        assert_eq!(place(&mut layout, 1, [8, 4], usize::MAX).unwrap().get_page(), 0);
+        let region = place(&mut layout, 2, [2, 2], usize::MAX).unwrap();
+        assert_eq!((region.get_page(), region.get_offset()), (1, [0, 0]));
+        assert_eq!(layout.pages.len(), 2);
+    }
+
+    #[test]
+    fn max_pages_exhausted() {
+        let mut layout = layout([8, 8], 0, 0);
+        place(&mut layout, 0, [8, 8], 1).unwrap();
+        assert!(place(&mut layout, 1, [1, 1], 1).is_err());
+        assert_eq!(layout.pages.len(), 1);
+        assert!(!layout.regions.contains_key(&1));
+    }
+
+    #[test]
+    fn image_too_big_or_with_wrong_len() {
+        let mut layout = layout([8, 8], 2, 1);
+        assert!(place(&mut layout, 0, [7, 6], usize::MAX).is_err());
+        assert!(place(&mut layout, 0, [0, 6], usize::MAX).is_err());
+        assert!(layout.place(&0, [2, 2], &[0; 3], usize::MAX).is_err());
+        assert!(layout.pages.is_empty());
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let mut layout = layout([8, 8], 0, 0);
+        place(&mut layout, 0, [2, 2], usize::MAX).unwrap();
+        assert!(place(&mut layout, 0, [2, 2], usize::MAX).is_err());
+        assert_eq!(layout.regions.len(), 1);
+    }
+
+    #[test]
+    fn undo_restores_the_layout() {
+        let mut layout = layout([8, 8], 0, 0);
+        place(&mut layout, 0, [4, 8], usize::MAX).unwrap();
+        let segments = layout.pages[0].segments.clone();
+
+        let (region, _, undo) = layout.place(&1, [2, 2], &[0; 4], usize::MAX).unwrap();
+        assert_eq!(region.get_page(), 0);
+        layout.undo(undo);
+        assert_eq!(layout.pages[0].segments, segments);
+
+        let (region, _, undo) = layout.place(&1, [8, 8], &[0; 64], usize::MAX).unwrap();
+        assert_eq!(region.get_page(), 1);
+        layout.undo(undo);
+        assert_eq!(layout.pages.len(), 1);
+        assert_eq!(place(&mut layout, 1, [4, 8], usize::MAX).unwrap().get_offset(), [4, 0]);
+    }
+
+    #[test]
+    fn extruded_edges() {
+        assert_eq!(extrude([2, 2], &[1, 2, 3, 4], 1, 1), [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]);
+        assert_eq!(extrude([2, 1], &[1, 2, 3, 4], 2, 1), [1, 2, 1, 2, 3, 4, 3, 4, 1, 2, 1, 2, 3, 4, 3, 4, 1, 2, 1, 2, 3, 4, 3, 4]);
+        assert_eq!(extrude([1, 1], &[5], 1, 0), [5]);
+    }
+
+    #[test]
+    fn uv() {
+        let mut layout = layout([16, 8], 0, 1);
+        let region = place(&mut layout, 0, [4, 2], usize::MAX).unwrap();
+        assert_eq!(region.get_offset(), [1, 1]);
+        assert_eq!(region.get_uv_min(), [1.0 / 16.0, 1.0 / 8.0]);
+        assert_eq!(region.get_uv_max(), [5.0 / 16.0, 3.0 / 8.0]);
+        let region = place(&mut layout, 1, [2, 2], usize::MAX).unwrap();
+        assert_eq!(region.get_offset(), [7, 1]);
+        assert_eq!(region.get_uv_min(), [7.0 / 16.0, 1.0 / 8.0]);
+        assert_eq!(region.get_uv_max(), [9.0 / 16.0, 3.0 / 8.0]);
+    }
+}
@@ -0,0 +1,409 @@
+use super::{get_size_of_mip_level, ByteReader, TextureContainer};
+use crate::unwrap_option_or_ret;
+use gl::types::*;
+
+pub(super) const DDS_MAGIC: &[u8; 4] = b"DDS ";
+
+const DDSD_DEPTH: u32 = 0x800000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x20000;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALL_FACES: u32 = 0xFC00;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+#[inline(always)]
+const fn four_cc(code: &[u8; 4]) -> u32 {
+    u32::from_le_bytes(*code)
+}
+
+/// Returns the opengl internal format and the type and format of the data ( None for compressed formats ) for a four character code
+/// NOTE: Besides actual character codes some writers put D3DFMT values in there for float formats
+fn four_cc_to_gl(code: u32) -> Option<(GLenum, Option<(GLenum, GLenum)>)> {
+    Some(match code {
+        c if c == four_cc(b"DXT1") => (crate::GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, None),
+        c if c == four_cc(b"DXT3") => (crate::GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, None),
+        c if c == four_cc(b"DXT5") => (crate::GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
+        c if c == four_cc(b"ATI1") || c == four_cc(b"BC4U") => (gl::COMPRESSED_RED_RGTC1, None),
+        c if c == four_cc(b"BC4S") => (gl::COMPRESSED_SIGNED_RED_RGTC1, None),
+        c if c == four_cc(b"ATI2") || c == four_cc(b"BC5U") => (gl::COMPRESSED_RG_RGTC2, None),
+        c if c == four_cc(b"BC5S") => (gl::COMPRESSED_SIGNED_RG_RGTC2, None),
+        36 => (gl::RGBA16, Some((gl::UNSIGNED_SHORT, gl::RGBA))),
+        111 => (gl::R16F, Some((gl::HALF_FLOAT, gl::RED))),
+        112 => (gl::RG16F, Some((gl::HALF_FLOAT, gl::RG))),
+        113 => (gl::RGBA16F, Some((gl::HALF_FLOAT, gl::RGBA))),
+        114 => (gl::R32F, Some((gl::FLOAT, gl::RED))),
+        115 => (gl::RG32F, Some((gl::FLOAT, gl::RG))),
+        116 => (gl::RGBA32F, Some((gl::FLOAT, gl::RGBA))),
+        _ => return None,
+    })
+}
+
+/// Returns the opengl internal format and the type and format of the data ( None for compressed formats ) for a DXGI format
+fn dxgi_format_to_gl(dxgi_format: u32) -> Option<(GLenum, Option<(GLenum, GLenum)>)> {
+    Some(match dxgi_format {
+        2 => (gl::RGBA32F, Some((gl::FLOAT, gl::RGBA))),
+        6 => (gl::RGB32F, Some((gl::FLOAT, gl::RGB))),
+        10 => (gl::RGBA16F, Some((gl::HALF_FLOAT, gl::RGBA))),
+        11 => (gl::RGBA16, Some((gl::UNSIGNED_SHORT, gl::RGBA))),
+        16 => (gl::RG32F, Some((gl::FLOAT, gl::RG))),
+        24 => (gl::RGB10_A2, Some((gl::UNSIGNED_INT_2_10_10_10_REV, gl::RGBA))),
+        26 => (gl::R11F_G11F_B10F, Some((gl::UNSIGNED_INT_10F_11F_11F_REV, gl::RGB))),
+        28 => (gl::RGBA8, Some((gl::UNSIGNED_BYTE, gl::RGBA))),
+        29 => (gl::SRGB8_ALPHA8, Some((gl::UNSIGNED_BYTE, gl::RGBA))),
+        34 => (gl::RG16F, Some((gl::HALF_FLOAT, gl::RG))),
+        35 => (gl::RG16, Some((gl::UNSIGNED_SHORT, gl::RG))),
+        40 => (gl::DEPTH_COMPONENT32F, Some((gl::FLOAT, gl::DEPTH_COMPONENT))),
+        41 => (gl::R32F, Some((gl::FLOAT, gl::RED))),
+        42 => (gl::R32UI, Some((gl::UNSIGNED_INT, gl::RED_INTEGER))),
+        49 => (gl::RG8, Some((gl::UNSIGNED_BYTE, gl::RG))),
+        54 => (gl::R16F, Some((gl::HALF_FLOAT, gl::RED))),
+        55 => (gl::DEPTH_COMPONENT16, Some((gl::UNSIGNED_SHORT, gl::DEPTH_COMPONENT))),
+        56 => (gl::R16, Some((gl::UNSIGNED_SHORT, gl::RED))),
+        61 => (gl::R8, Some((gl::UNSIGNED_BYTE, gl::RED))),
+        71 => (crate::GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, None),
+        72 => (crate::GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT, None),
+        74 => (crate::GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, None),
+        75 => (crate::GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT, None),
+        77 => (crate::GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
+        78 => (crate::GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT, None),
+        80 => (gl::COMPRESSED_RED_RGTC1, None),
+        81 => (gl::COMPRESSED_SIGNED_RED_RGTC1, None),
+        83 => (gl::COMPRESSED_RG_RGTC2, None),
+        84 => (gl::COMPRESSED_SIGNED_RG_RGTC2, None),
+        87 => (gl::RGBA8, Some((gl::UNSIGNED_BYTE, gl::BGRA))),
+        91 => (gl::SRGB8_ALPHA8, Some((gl::UNSIGNED_BYTE, gl::BGRA))),
+        95 => (gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT, None),
+        96 => (gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT, None),
+        98 => (gl::COMPRESSED_RGBA_BPTC_UNORM, None),
+        99 => (gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM, None),
+        _ => return None,
+    })
+}
+
+/// Legacy uncompressed DDS files describe their pixels with bit masks, only the common 8 bit per channel layouts are supported
+fn masks_to_gl(flags: u32, bits: u32, masks: [u32; 4]) -> Option<(GLenum, Option<(GLenum, GLenum)>)> {
+    let has_alpha = flags & DDPF_ALPHAPIXELS != 0;
+    Some(if flags & DDPF_RGB != 0 {
+        let internal_format = if has_alpha { gl::RGBA8 } else { gl::RGB8 };
+        match (bits, masks[0], masks[1], masks[2]) {
+            (32, 0xFF, 0xFF00, 0xFF0000) => (internal_format, Some((gl::UNSIGNED_BYTE, gl::RGBA))),
+            (32, 0xFF0000, 0xFF00, 0xFF) => (internal_format, Some((gl::UNSIGNED_BYTE, gl::BGRA))),
+            (24, 0xFF, 0xFF00, 0xFF0000) => (gl::RGB8, Some((gl::UNSIGNED_BYTE, gl::RGB))),
+            (24, 0xFF0000, 0xFF00, 0xFF) => (gl::RGB8, Some((gl::UNSIGNED_BYTE, gl::BGR))),
+            _ => return None,
+        }
+    } else if flags & DDPF_LUMINANCE != 0 {
+        match (bits, has_alpha) {
+            (8, false) => (gl::R8, Some((gl::UNSIGNED_BYTE, gl::RED))),
+            // Luminance in the low byte and alpha in the high one, which reads as red and green
+            (16, true) if masks[0] == 0xFF && masks[3] == 0xFF00 => (gl::RG8, Some((gl::UNSIGNED_BYTE, gl::RG))),
+            _ => return None,
+        }
+    } else {
+        return None;
+    })
+}
+
+/// DDS files store every mip level of one image ( layer or face ) before the next image, unlike opengl and KTX which go level by level
+pub(super) fn parse_dds(bytes: &[u8]) -> Result<TextureContainer, String> {
+    let mut r = ByteReader::new(bytes);
+    if r.read_bytes(DDS_MAGIC.len())? != DDS_MAGIC {
+        return Err("Not a DDS file!".to_owned());
+    }
+    if r.read_u32()? != 124 {
+        return Err("Invalid DDS header size!".to_owned());
+    }
+    let flags = r.read_u32()?;
+    let height = r.read_usize()?;
+    let width = r.read_usize()?;
+    let _pitch_or_linear_size = r.read_u32()?;
+    let depth = r.read_usize()?;
+    let mip_count = r.read_usize()?;
+    r.skip(11 * 4)?;
+    let _pixel_format_size = r.read_u32()?;
+    let pixel_format_flags = r.read_u32()?;
+    let code = r.read_u32()?;
+    let bits = r.read_u32()?;
+    let masks = [r.read_u32()?, r.read_u32()?, r.read_u32()?, r.read_u32()?];
+    let _caps = r.read_u32()?;
+    let caps2 = r.read_u32()?;
+    r.skip(3 * 4)?;
+
+    let mut faces = 1;
+    if caps2 & DDSCAPS2_CUBEMAP != 0 {
+        if caps2 & DDSCAPS2_CUBEMAP_ALL_FACES != DDSCAPS2_CUBEMAP_ALL_FACES {
+            return Err("Cube maps without all 6 faces are not supported!".to_owned());
+        }
+        faces = 6;
+    }
+    let mut layers = None;
+    let formats = if pixel_format_flags & DDPF_FOURCC == 0 {
+        masks_to_gl(pixel_format_flags, bits, masks)
+    } else if code == four_cc(b"DX10") {
+        let dxgi_format = r.read_u32()?;
+        let _resource_dimension = r.read_u32()?;
+        let misc_flags = r.read_u32()?;
+        let array_size = r.read_usize()?;
+        let _misc_flags2 = r.read_u32()?;
+        if misc_flags & DDS_RESOURCE_MISC_TEXTURECUBE != 0 {
+            faces = 6;
+        }
+        // NOTE: For cube maps the array size is the number of cubes, not of faces
+        if array_size > 1 {
+            layers = Some(array_size);
+        }
+        dxgi_format_to_gl(dxgi_format)
+    } else {
+        four_cc_to_gl(code)
+    };
+    let (internal_format, data_format) = unwrap_option_or_ret!(formats, Err("Unsupported DDS pixel format!".to_owned()));
+
+    let depth = if flags & DDSD_DEPTH != 0 && caps2 & DDSCAPS2_VOLUME != 0 { depth.max(1) } else { 1 };
+    let size = [width, height, depth];
+    let levels = if flags & DDSD_MIPMAPCOUNT != 0 { mip_count.max(1) } else { 1 };
+    let mut image_lens = Vec::new();
+    for level in 0..levels {
+        let level_size = get_size_of_mip_level(size, level);
+        let len = match data_format {
+            None => crate::get_compressed_image_size(internal_format, &level_size),
+            Some((typ, format)) => crate::get_elements_per_pixel(typ, format)
+                .zip(crate::get_size_of_gl_type(typ))
+                .and_then(|(epp, type_size)| level_size.iter().try_fold(usize::from(epp) * usize::from(type_size), |acc, s| acc.checked_mul(*s))),
+        };
+        image_lens.push(unwrap_option_or_ret!(len, Err("Texture too big!".to_owned())));
+        // Once every dimension is 1 there are no more levels, so a bogus mip count can't make this loop for long
+        if level_size == [1, 1, 1] {
+            break;
+        }
+    }
+
+    let images = unwrap_option_or_ret!(layers.unwrap_or(1).checked_mul(faces), Err("Texture too big!".to_owned()));
+    let mut level_data = vec![Vec::new(); image_lens.len()];
+    for _ in 0..images {
+        for (data, len) in level_data.iter_mut().zip(image_lens.iter()) {
+            data.extend_from_slice(r.read_bytes(*len)?);
+        }
+    }
+    TextureContainer::new(internal_format, data_format, size, layers, faces, level_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DX10: u32 = u32::from_le_bytes(*b"DX10");
+
+    struct Header {
+        size: [u32; 3],
+        mip_count: Option<u32>,
+        pixel_format_flags: u32,
+        code: u32,
+        bits: u32,
+        masks: [u32; 4],
+        caps2: u32,
+        /// dxgi format, misc flags and array size of the DX10 header
+        dx10: Option<(u32, u32, u32)>,
+    }
+
+    impl Header {
+        fn rgba(masks: [u32; 4], pixel_format_flags: u32, bits: u32) -> Self {
+            Header {
+                size: [2, 2, 1],
+                mip_count: None,
+                pixel_format_flags,
+                code: 0,
+                bits,
+                masks,
+                caps2: 0,
+                dx10: None,
+            }
+        }
+
+        fn dx10(dxgi_format: u32, misc_flags: u32, array_size: u32) -> Self {
+            Header {
+                code: DX10,
+                dx10: Some((dxgi_format, misc_flags, array_size)),
+                ..Self::rgba([0; 4], DDPF_FOURCC, 0)
+            }
+        }
+
+        fn to_bytes(&self) -> Vec<u8> {
+            let mut flags = 0x1007;
+            if self.mip_count.is_some() {
+                flags |= DDSD_MIPMAPCOUNT;
+            }
+            if self.size[2] > 1 {
+                flags |= DDSD_DEPTH;
+            }
+            let mut fields = vec![124, flags, self.size[1], self.size[0], 0, self.size[2], self.mip_count.unwrap_or(0)];
+            fields.extend_from_slice(&[0; 11]);
+            fields.extend_from_slice(&[32, self.pixel_format_flags, self.code, self.bits]);
+            fields.extend_from_slice(&self.masks);
+            fields.extend_from_slice(&[0x1000, self.caps2, 0, 0, 0]);
+            if let Some((dxgi_format, misc_flags, array_size)) = self.dx10 {
+                fields.extend_from_slice(&[dxgi_format, 3, misc_flags, array_size, 0]);
+            }
+            let mut r = DDS_MAGIC.to_vec();
+            r.extend(fields.iter().flat_map(|field| field.to_le_bytes()));
+            r
+        }
+    }
+
+    fn parse(header: &Header, data: &[u8]) -> Result<TextureContainer, String> {
+        let mut bytes = header.to_bytes();
+        bytes.extend_from_slice(data);
+        TextureContainer::from_bytes(&bytes)
+    }
+
+    #[test]
+    fn legacy_bgra_masks() {
+        let header = Header::rgba([0xFF0000, 0xFF00, 0xFF, 0xFF000000], DDPF_RGB | DDPF_ALPHAPIXELS, 32);
+        let data: Vec<u8> = (0..16).collect();
+        let tex = parse(&header, &data).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RGBA8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::BGRA)));
+        assert_eq!(tex.get_size(), [2, 2, 1]);
+        assert_eq!(tex.get_num_of_levels(), 1);
+        assert_eq!(tex.get_image(0, 0, 0), Some(data.as_slice()));
+    }
+
+    #[test]
+    fn legacy_x8r8g8b8_keeps_4_bytes_per_pixel() {
+        let header = Header::rgba([0xFF0000, 0xFF00, 0xFF, 0], DDPF_RGB, 32);
+        let tex = parse(&header, &[7; 16]).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RGB8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::BGRA)));
+        assert_eq!(tex.get_level_data(0).map(|data| data.len()), Some(16));
+    }
+
+    #[test]
+    fn legacy_rgba_and_bgr_masks() {
+        let header = Header::rgba([0xFF, 0xFF00, 0xFF0000, 0xFF000000], DDPF_RGB | DDPF_ALPHAPIXELS, 32);
+        assert_eq!(parse(&header, &[0; 16]).unwrap().get_data_format(), Some((gl::UNSIGNED_BYTE, gl::RGBA)));
+        let header = Header::rgba([0xFF0000, 0xFF00, 0xFF, 0], DDPF_RGB, 24);
+        let tex = parse(&header, &[0; 12]).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RGB8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::BGR)));
+    }
+
+    #[test]
+    fn legacy_luminance_alpha_masks() {
+        let header = Header::rgba([0xFF, 0, 0, 0xFF00], DDPF_LUMINANCE | DDPF_ALPHAPIXELS, 16);
+        let tex = parse(&header, &[0; 8]).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RG8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::RG)));
+        let header = Header::rgba([0xFF00, 0, 0, 0xFF], DDPF_LUMINANCE | DDPF_ALPHAPIXELS, 16);
+        assert!(parse(&header, &[0; 8]).is_err());
+        let header = Header::rgba([0xF, 0, 0, 0xF0], DDPF_LUMINANCE | DDPF_ALPHAPIXELS, 16);
+        assert!(parse(&header, &[0; 8]).is_err());
+    }
+
+    #[test]
+    fn unsupported_masks() {
+        let header = Header::rgba([0x7C00, 0x3E0, 0x1F, 0], DDPF_RGB, 16);
+        assert!(parse(&header, &[0; 8]).is_err());
+    }
+
+    #[test]
+    fn mip_sizes() {
+        let mut header = Header::dx10(28, 0, 1);
+        header.size = [4, 2, 1];
+        header.mip_count = Some(3);
+        let tex = parse(&header, &[0; 32 + 8 + 4]).unwrap();
+        assert_eq!(tex.get_num_of_levels(), 3);
+        assert_eq!(tex.get_size_of_level(1), [2, 1, 1]);
+        assert_eq!(tex.get_size_of_level(2), [1, 1, 1]);
+        let lens: Vec<usize> = (0..3).map(|level| tex.get_level_data(level).unwrap().len()).collect();
+        assert_eq!(lens, [32, 8, 4]);
+    }
+
+    #[test]
+    fn bogus_mip_count_stops_at_1x1() {
+        let mut header = Header::dx10(28, 0, 1);
+        header.size = [1, 1, 1];
+        header.mip_count = Some(u32::MAX);
+        assert_eq!(parse(&header, &[0; 4]).unwrap().get_num_of_levels(), 1);
+    }
+
+    #[test]
+    fn cube_map_is_reordered_level_by_level() {
+        let mut header = Header::rgba([0xFF, 0xFF00, 0xFF0000, 0xFF000000], DDPF_RGB | DDPF_ALPHAPIXELS, 32);
+        header.mip_count = Some(2);
+        header.caps2 = DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALL_FACES;
+        // DDS stores every mip level of a face before the next face
+        let mut data = Vec::new();
+        for face in 0..6u8 {
+            data.extend_from_slice(&[face * 2; 16]);
+            data.extend_from_slice(&[face * 2 + 1; 4]);
+        }
+        let tex = parse(&header, &data).unwrap();
+        assert_eq!(tex.get_faces(), 6);
+        assert_eq!(tex.get_layers(), None);
+        for face in 0..6u8 {
+            assert_eq!(tex.get_image(0, 0, usize::from(face)), Some(&[face * 2; 16][..]));
+            assert_eq!(tex.get_image(1, 0, usize::from(face)), Some(&[face * 2 + 1; 4][..]));
+        }
+        assert_eq!(tex.get_image(0, 0, 6), None);
+    }
+
+    #[test]
+    fn cube_map_without_all_faces() {
+        let mut header = Header::rgba([0xFF, 0xFF00, 0xFF0000, 0xFF000000], DDPF_RGB | DDPF_ALPHAPIXELS, 32);
+        header.caps2 = DDSCAPS2_CUBEMAP | 0x400;
+        assert!(parse(&header, &[0; 16 * 6]).is_err());
+    }
+
+    #[test]
+    fn dx10_array() {
+        let mut header = Header::dx10(87, 0, 3);
+        header.size = [1, 1, 1];
+        let data: Vec<u8> = (0..12).collect();
+        let tex = parse(&header, &data).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RGBA8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::BGRA)));
+        assert_eq!(tex.get_layers(), Some(3));
+        assert_eq!(tex.get_image(0, 2, 0), Some(&data[8..12]));
+        assert_eq!(tex.get_image(0, 3, 0), None);
+    }
+
+    #[test]
+    fn dx10_cube_map_array_counts_cubes() {
+        let mut header = Header::dx10(28, DDS_RESOURCE_MISC_TEXTURECUBE, 2);
+        header.size = [1, 1, 1];
+        let tex = parse(&header, &[0; 4 * 6 * 2]).unwrap();
+        assert_eq!(tex.get_faces(), 6);
+        assert_eq!(tex.get_layers(), Some(2));
+    }
+
+    #[test]
+    fn dx10_compressed() {
+        let mut header = Header::dx10(71, 0, 1);
+        header.size = [8, 4, 1];
+        header.mip_count = Some(2);
+        let tex = parse(&header, &[0; 16 + 8]).unwrap();
+        assert_eq!(tex.get_internal_format(), crate::GL_COMPRESSED_RGBA_S3TC_DXT1_EXT);
+        assert_eq!(tex.get_data_format(), None);
+        assert_eq!(tex.get_level_data(1).map(|data| data.len()), Some(8));
+    }
+
+    #[test]
+    fn malformed_input() {
+        let header = Header::rgba([0xFF0000, 0xFF00, 0xFF, 0xFF000000], DDPF_RGB | DDPF_ALPHAPIXELS, 32);
+        let bytes = header.to_bytes();
+        assert_eq!(parse(&header, &[0; 15]), Err("File is truncated!".to_owned()));
+        assert_eq!(TextureContainer::from_bytes(&bytes[..60]), Err("File is truncated!".to_owned()));
+        let mut bad_size = bytes.clone();
+        bad_size[4] = 100;
+        assert!(TextureContainer::from_bytes(&bad_size).is_err());
+        let unknown = Header {
+            code: u32::from_le_bytes(*b"XXXX"),
+            ..Header::rgba([0; 4], DDPF_FOURCC, 0)
+        };
+        assert!(parse(&unknown, &[0; 16]).is_err());
+        assert!(parse(&Header::dx10(1000, 0, 1), &[0; 16]).is_err());
+    }
+}
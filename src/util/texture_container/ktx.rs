@@ -0,0 +1,392 @@
+use super::{get_size_of_mip_level, ByteReader, TextureContainer};
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret};
+use gl::types::*;
+use std::convert::TryInto;
+
+pub(super) const KTX1_IDENTIFIER: [u8; 12] = [0xAB, b'K', b'T', b'X', b' ', b'1', b'1', 0xBB, b'\r', b'\n', 0x1A, b'\n'];
+pub(super) const KTX2_IDENTIFIER: [u8; 12] = [0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n'];
+
+/// KTX1 files store the values of the opengl enums directly, so the only thing to do is read them and undo the row and mip padding
+pub(super) fn parse_ktx1(bytes: &[u8]) -> Result<TextureContainer, String> {
+    let mut r = ByteReader::new(bytes);
+    if r.read_bytes(KTX1_IDENTIFIER.len())? != KTX1_IDENTIFIER {
+        return Err("Not a KTX1 file!".to_owned());
+    }
+    // NOTE: The endianness field is always written as 0x04030201 in the endianness of the file
+    r.big_endian = match r.read_u32()? {
+        0x04030201 => false,
+        0x01020304 => true,
+        _ => return Err("Invalid endianness in KTX1 file!".to_owned()),
+    };
+    let typ = r.read_u32()?;
+    let type_size = r.read_usize()?;
+    let format = r.read_u32()?;
+    let internal_format = r.read_u32()?;
+    let _base_internal_format = r.read_u32()?;
+    let width = r.read_usize()?;
+    let height = r.read_usize()?;
+    let depth = r.read_usize()?;
+    let array_elements = r.read_usize()?;
+    let faces = r.read_usize()?;
+    let levels = r.read_usize()?;
+    let key_value_len = r.read_usize()?;
+    r.skip(key_value_len)?;
+
+    if height == 0 {
+        return Err("1D textures are not supported!".to_owned());
+    }
+    let size = [width, height, depth.max(1)];
+    // glType is 0 for compressed formats
+    let data_format = if typ == 0 { None } else { Some((typ, format)) };
+    let layers = if array_elements == 0 { None } else { Some(array_elements) };
+    let images = unwrap_option_or_ret!(layers.unwrap_or(1).checked_mul(faces), Err("Texture too big!".to_owned()));
+    // The image size of non array cube maps is the size of one face, for everything else it's the size of the whole level
+    let is_cube = faces == 6 && layers.is_none();
+
+    let mut level_data = Vec::new();
+    // NOTE: 0 mip levels means the file only stores level 0 and the rest should be generated
+    for level in 0..levels.max(1) {
+        let image_size = r.read_usize()?;
+        let [w, h, d] = get_size_of_mip_level(size, level);
+        // Uncompressed rows are padded to 4 bytes, compressed data is made of whole blocks which are already multiples of 4 bytes
+        let (row_len, padded_row_len, rows) = match data_format {
+            None => {
+                let len = unwrap_option_or_ret!(
+                    crate::get_compressed_image_size(internal_format, &[w, h, d]),
+                    Err(format!("Unsupported compressed internal format: {:#x}!", internal_format))
+                );
+                (len, len, 1)
+            }
+            Some((typ, format)) => {
+                let epp = unwrap_option_or_ret!(crate::get_elements_per_pixel(typ, format), Err(format!("Unsupported type {:#x} and format {:#x}!", typ, format)));
+                let bpp = usize::from(epp) * usize::from(unwrap_option_or_ret!(crate::get_size_of_gl_type(typ), Err(format!("Unsupported type {:#x}!", typ))));
+                let row_len = unwrap_option_or_ret!(w.checked_mul(bpp), Err("Texture too big!".to_owned()));
+                let rows = unwrap_option_or_ret!(h.checked_mul(d), Err("Texture too big!".to_owned()));
+                (row_len, row_len + (4 - row_len % 4) % 4, rows)
+            }
+        };
+        let padded_image_len = unwrap_option_or_ret!(padded_row_len.checked_mul(rows), Err("Texture too big!".to_owned()));
+        let expected_size = if is_cube { Some(padded_image_len) } else { padded_image_len.checked_mul(images) };
+        if Some(image_size) != expected_size {
+            return Err(format!("Image size of mip level {} is {} bytes but {:?} bytes were expected!", level, image_size, expected_size));
+        }
+
+        let mut data = Vec::new();
+        for _ in 0..images {
+            for _ in 0..rows {
+                data.extend_from_slice(&r.read_bytes(padded_row_len)?[..row_len]);
+            }
+            if is_cube {
+                r.align(4)?;
+            }
+        }
+        r.align(4)?;
+        if r.big_endian && type_size > 1 {
+            for element in data.chunks_exact_mut(type_size) {
+                element.reverse();
+            }
+        }
+        level_data.push(data);
+    }
+
+    let mut r = TextureContainer::new(internal_format, data_format, size, layers, faces, level_data)?;
+    r.generate_mipmaps = levels == 0;
+    Ok(r)
+}
+
+/// Returns the opengl internal format and the type and format of the data ( None for compressed formats ) for a vulkan format
+fn vk_format_to_gl(vk_format: u32) -> Option<(GLenum, Option<(GLenum, GLenum)>)> {
+    Some(match vk_format {
+        9 => (gl::R8, Some((gl::UNSIGNED_BYTE, gl::RED))),
+        16 => (gl::RG8, Some((gl::UNSIGNED_BYTE, gl::RG))),
+        23 => (gl::RGB8, Some((gl::UNSIGNED_BYTE, gl::RGB))),
+        29 => (gl::SRGB8, Some((gl::UNSIGNED_BYTE, gl::RGB))),
+        37 => (gl::RGBA8, Some((gl::UNSIGNED_BYTE, gl::RGBA))),
+        43 => (gl::SRGB8_ALPHA8, Some((gl::UNSIGNED_BYTE, gl::RGBA))),
+        44 => (gl::RGBA8, Some((gl::UNSIGNED_BYTE, gl::BGRA))),
+        50 => (gl::SRGB8_ALPHA8, Some((gl::UNSIGNED_BYTE, gl::BGRA))),
+        64 => (gl::RGB10_A2, Some((gl::UNSIGNED_INT_2_10_10_10_REV, gl::RGBA))),
+        70 => (gl::R16, Some((gl::UNSIGNED_SHORT, gl::RED))),
+        76 => (gl::R16F, Some((gl::HALF_FLOAT, gl::RED))),
+        77 => (gl::RG16, Some((gl::UNSIGNED_SHORT, gl::RG))),
+        83 => (gl::RG16F, Some((gl::HALF_FLOAT, gl::RG))),
+        84 => (gl::RGB16, Some((gl::UNSIGNED_SHORT, gl::RGB))),
+        90 => (gl::RGB16F, Some((gl::HALF_FLOAT, gl::RGB))),
+        91 => (gl::RGBA16, Some((gl::UNSIGNED_SHORT, gl::RGBA))),
+        97 => (gl::RGBA16F, Some((gl::HALF_FLOAT, gl::RGBA))),
+        98 => (gl::R32UI, Some((gl::UNSIGNED_INT, gl::RED_INTEGER))),
+        100 => (gl::R32F, Some((gl::FLOAT, gl::RED))),
+        103 => (gl::RG32F, Some((gl::FLOAT, gl::RG))),
+        106 => (gl::RGB32F, Some((gl::FLOAT, gl::RGB))),
+        109 => (gl::RGBA32F, Some((gl::FLOAT, gl::RGBA))),
+        122 => (gl::R11F_G11F_B10F, Some((gl::UNSIGNED_INT_10F_11F_11F_REV, gl::RGB))),
+        124 => (gl::DEPTH_COMPONENT16, Some((gl::UNSIGNED_SHORT, gl::DEPTH_COMPONENT))),
+        126 => (gl::DEPTH_COMPONENT32F, Some((gl::FLOAT, gl::DEPTH_COMPONENT))),
+        131 => (crate::GL_COMPRESSED_RGB_S3TC_DXT1_EXT, None),
+        132 => (crate::GL_COMPRESSED_SRGB_S3TC_DXT1_EXT, None),
+        133 => (crate::GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, None),
+        134 => (crate::GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT, None),
+        135 => (crate::GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, None),
+        136 => (crate::GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT, None),
+        137 => (crate::GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
+        138 => (crate::GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT, None),
+        139 => (gl::COMPRESSED_RED_RGTC1, None),
+        140 => (gl::COMPRESSED_SIGNED_RED_RGTC1, None),
+        141 => (gl::COMPRESSED_RG_RGTC2, None),
+        142 => (gl::COMPRESSED_SIGNED_RG_RGTC2, None),
+        143 => (gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT, None),
+        144 => (gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT, None),
+        145 => (gl::COMPRESSED_RGBA_BPTC_UNORM, None),
+        146 => (gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM, None),
+        147 => (gl::COMPRESSED_RGB8_ETC2, None),
+        148 => (gl::COMPRESSED_SRGB8_ETC2, None),
+        149 => (gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2, None),
+        150 => (gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2, None),
+        151 => (gl::COMPRESSED_RGBA8_ETC2_EAC, None),
+        152 => (gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC, None),
+        153 => (gl::COMPRESSED_R11_EAC, None),
+        154 => (gl::COMPRESSED_SIGNED_R11_EAC, None),
+        155 => (gl::COMPRESSED_RG11_EAC, None),
+        156 => (gl::COMPRESSED_SIGNED_RG11_EAC, None),
+        _ => return None,
+    })
+}
+
+/// KTX2 files use vulkan formats and an index of where every mip level is, the data itself is tightly packed
+pub(super) fn parse_ktx2(bytes: &[u8]) -> Result<TextureContainer, String> {
+    let mut r = ByteReader::new(bytes);
+    if r.read_bytes(KTX2_IDENTIFIER.len())? != KTX2_IDENTIFIER {
+        return Err("Not a KTX2 file!".to_owned());
+    }
+    let vk_format = r.read_u32()?;
+    let _type_size = r.read_u32()?;
+    let width = r.read_usize()?;
+    let height = r.read_usize()?;
+    let depth = r.read_usize()?;
+    let layer_count = r.read_usize()?;
+    let faces = r.read_usize()?;
+    let levels = r.read_usize()?;
+    let supercompression = r.read_u32()?;
+    // Data format descriptor, key/value data and supercompression global data are not needed
+    r.skip(4 * 4 + 2 * 8)?;
+
+    if supercompression != 0 {
+        return Err(format!("Supercompressed KTX2 files are not supported ( scheme {} )!", supercompression));
+    }
+    let (internal_format, data_format) = unwrap_option_or_ret!(vk_format_to_gl(vk_format), Err(format!("Unsupported vulkan format {} in KTX2 file!", vk_format)));
+    if height == 0 {
+        return Err("1D textures are not supported!".to_owned());
+    }
+    let layers = if layer_count == 0 { None } else { Some(layer_count) };
+
+    let mut level_data = Vec::new();
+    // NOTE: Just like in KTX1 0 mip levels means the file only stores level 0 and the rest should be generated
+    for _ in 0..levels.max(1) {
+        let offset: usize = unwrap_result_or_ret!(r.read_u64()?.try_into(), Err("Mip level offset too big for this platform!".to_owned()));
+        let len: usize = unwrap_result_or_ret!(r.read_u64()?.try_into(), Err("Mip level too big for this platform!".to_owned()));
+        let _uncompressed_len = r.read_u64()?;
+        let end = unwrap_option_or_ret!(offset.checked_add(len), Err("File is truncated!".to_owned()));
+        let data = unwrap_option_or_ret!(bytes.get(offset..end), Err("File is truncated!".to_owned()));
+        level_data.push(data.to_vec());
+    }
+
+    let mut r = TextureContainer::new(internal_format, data_format, [width, height, depth.max(1)], layers, faces, level_data)?;
+    r.generate_mipmaps = levels == 0;
+    Ok(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// type, type size, format, internal format, width, height, depth, array elements, faces and mip levels
+    fn ktx1_header(fields: [u32; 10], key_value_data: &[u8], big_endian: bool) -> Vec<u8> {
+        let to_bytes = |val: u32| if big_endian { val.to_be_bytes() } else { val.to_le_bytes() };
+        let mut r = KTX1_IDENTIFIER.to_vec();
+        r.extend_from_slice(&to_bytes(0x04030201));
+        let [typ, type_size, format, internal_format, rest @ ..] = fields;
+        for val in [typ, type_size, format, internal_format, format] {
+            r.extend_from_slice(&to_bytes(val));
+        }
+        for val in rest {
+            r.extend_from_slice(&to_bytes(val));
+        }
+        r.extend_from_slice(&to_bytes(key_value_data.len() as u32));
+        r.extend_from_slice(key_value_data);
+        r
+    }
+
+    fn push_ktx1_level(bytes: &mut Vec<u8>, image_size: u32, data: &[u8]) {
+        bytes.extend_from_slice(&image_size.to_le_bytes());
+        bytes.extend_from_slice(data);
+    }
+
+    fn rgba8_ktx1(size: [u32; 3], array_elements: u32, faces: u32, levels: u32) -> Vec<u8> {
+        let [w, h, d] = size;
+        ktx1_header([gl::UNSIGNED_BYTE, 1, gl::RGBA, gl::RGBA8, w, h, d, array_elements, faces, levels], &[], false)
+    }
+
+    #[test]
+    fn ktx1_rows_are_unpadded() {
+        let mut bytes = ktx1_header([gl::UNSIGNED_BYTE, 1, gl::RGB, gl::RGB8, 3, 2, 0, 0, 1, 1], &[1, 2, 3, 4], false);
+        // Rows of 9 bytes are padded to 12
+        let mut data = Vec::new();
+        for row in 0..2u8 {
+            data.extend_from_slice(&[row; 9]);
+            data.extend_from_slice(&[0xEE; 3]);
+        }
+        push_ktx1_level(&mut bytes, 24, &data);
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RGB8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::RGB)));
+        assert_eq!(tex.get_size(), [3, 2, 1]);
+        let mut expected = vec![0; 9];
+        expected.extend_from_slice(&[1; 9]);
+        assert_eq!(tex.get_level_data(0), Some(expected.as_slice()));
+    }
+
+    #[test]
+    fn ktx1_mip_levels() {
+        let mut bytes = rgba8_ktx1([4, 2, 0], 0, 1, 3);
+        push_ktx1_level(&mut bytes, 32, &[0; 32]);
+        push_ktx1_level(&mut bytes, 8, &[1; 8]);
+        push_ktx1_level(&mut bytes, 4, &[2; 4]);
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_num_of_levels(), 3);
+        assert_eq!(tex.get_size_of_level(1), [2, 1, 1]);
+        assert_eq!(tex.get_level_data(2), Some(&[2; 4][..]));
+        assert!(!tex.should_generate_mipmaps());
+    }
+
+    #[test]
+    fn ktx1_no_levels_means_generate_mipmaps() {
+        let mut bytes = rgba8_ktx1([2, 2, 0], 0, 1, 0);
+        push_ktx1_level(&mut bytes, 16, &[0; 16]);
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_num_of_levels(), 1);
+        assert!(tex.should_generate_mipmaps());
+    }
+
+    #[test]
+    fn ktx1_cube_map_image_size_is_one_face() {
+        let mut bytes = rgba8_ktx1([1, 1, 0], 0, 6, 1);
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        for face in 0..6u8 {
+            bytes.extend_from_slice(&[face; 4]);
+        }
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_faces(), 6);
+        assert_eq!(tex.get_layers(), None);
+        for face in 0..6u8 {
+            assert_eq!(tex.get_image(0, 0, usize::from(face)), Some(&[face; 4][..]));
+        }
+    }
+
+    #[test]
+    fn ktx1_array() {
+        let mut bytes = rgba8_ktx1([1, 1, 0], 2, 1, 1);
+        push_ktx1_level(&mut bytes, 8, &[0, 0, 0, 0, 1, 1, 1, 1]);
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_layers(), Some(2));
+        assert_eq!(tex.get_image(0, 1, 0), Some(&[1; 4][..]));
+    }
+
+    #[test]
+    fn ktx1_big_endian_elements_are_swapped() {
+        let mut bytes = ktx1_header([gl::UNSIGNED_SHORT, 2, gl::RED, gl::R16, 2, 1, 0, 0, 1, 1], &[], true);
+        bytes.extend_from_slice(&4u32.to_be_bytes());
+        bytes.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_level_data(0), Some(&[0x02, 0x01, 0x04, 0x03][..]));
+    }
+
+    #[test]
+    fn ktx1_malformed_input() {
+        let mut bytes = rgba8_ktx1([2, 2, 0], 0, 1, 1);
+        push_ktx1_level(&mut bytes, 12, &[0; 16]);
+        assert!(TextureContainer::from_bytes(&bytes).is_err());
+
+        let mut bytes = rgba8_ktx1([2, 2, 0], 0, 1, 1);
+        push_ktx1_level(&mut bytes, 16, &[0; 15]);
+        assert_eq!(TextureContainer::from_bytes(&bytes), Err("File is truncated!".to_owned()));
+
+        let mut bytes = rgba8_ktx1([2, 2, 0], 0, 1, 1);
+        bytes[12] = 0xFF;
+        assert_eq!(TextureContainer::from_bytes(&bytes), Err("Invalid endianness in KTX1 file!".to_owned()));
+
+        let bytes = rgba8_ktx1([2, 0, 0], 0, 1, 1);
+        assert!(TextureContainer::from_bytes(&bytes).is_err());
+        assert!(TextureContainer::from_bytes(&KTX1_IDENTIFIER[..8]).is_err());
+    }
+
+    /// vulkan format, width, height, depth, layers, faces, mip levels and supercompression scheme, levels are the data of every level
+    fn ktx2(fields: [u32; 8], levels: &[&[u8]]) -> Vec<u8> {
+        let mut r = KTX2_IDENTIFIER.to_vec();
+        let [vk_format, rest @ ..] = fields;
+        r.extend_from_slice(&vk_format.to_le_bytes());
+        r.extend_from_slice(&1u32.to_le_bytes());
+        for val in rest {
+            r.extend_from_slice(&val.to_le_bytes());
+        }
+        r.extend_from_slice(&[0; 4 * 4 + 2 * 8]);
+        let mut offset = (r.len() + levels.len() * 3 * 8) as u64;
+        for level in levels {
+            let len = level.len() as u64;
+            for val in [offset, len, len] {
+                r.extend_from_slice(&val.to_le_bytes());
+            }
+            offset += len;
+        }
+        for level in levels {
+            r.extend_from_slice(level);
+        }
+        r
+    }
+
+    #[test]
+    fn ktx2_bgra() {
+        let level0: Vec<u8> = (0..16).collect();
+        let bytes = ktx2([44, 2, 2, 0, 0, 1, 2, 0], &[&level0, &[1; 4]]);
+        let tex = TextureContainer::from_bytes(&bytes).unwrap();
+        assert_eq!(tex.get_internal_format(), gl::RGBA8);
+        assert_eq!(tex.get_data_format(), Some((gl::UNSIGNED_BYTE, gl::BGRA)));
+        assert_eq!(tex.get_num_of_levels(), 2);
+        assert_eq!(tex.get_level_data(0), Some(level0.as_slice()));
+        assert_eq!(tex.get_level_data(1), Some(&[1; 4][..]));
+        let bytes = ktx2([50, 1, 1, 0, 0, 1, 1, 0], &[&[0; 4]]);
+        assert_eq!(TextureContainer::from_bytes(&bytes).unwrap().get_internal_format(), gl::SRGB8_ALPHA8);
+    }
+
+    #[test]
+    fn ktx2_array_and_cube_map() {
+        let bytes = ktx2([37, 1, 1, 0, 3, 1, 1, 0], &[&[0; 12]]);
+        assert_eq!(TextureContainer::from_bytes(&bytes).unwrap().get_layers(), Some(3));
+        let faces: Vec<u8> = (0..6).flat_map(|face| [face; 4]).collect();
+        let tex = TextureContainer::from_bytes(&ktx2([37, 1, 1, 0, 0, 6, 1, 0], &[&faces])).unwrap();
+        assert_eq!(tex.get_faces(), 6);
+        assert_eq!(tex.get_image(0, 0, 5), Some(&[5; 4][..]));
+    }
+
+    #[test]
+    fn ktx2_no_levels_means_generate_mipmaps() {
+        let tex = TextureContainer::from_bytes(&ktx2([37, 2, 2, 0, 0, 1, 0, 0], &[&[0; 16]])).unwrap();
+        assert!(tex.should_generate_mipmaps());
+    }
+
+    #[test]
+    fn ktx2_malformed_input() {
+        assert!(TextureContainer::from_bytes(&ktx2([37, 1, 1, 0, 0, 1, 1, 1], &[&[0; 4]])).is_err());
+        assert!(TextureContainer::from_bytes(&ktx2([1000, 1, 1, 0, 0, 1, 1, 0], &[&[0; 4]])).is_err());
+        // Level length that doesn't match the size of the texture
+        assert!(TextureContainer::from_bytes(&ktx2([37, 1, 1, 0, 0, 1, 1, 0], &[&[0; 8]])).is_err());
+        let mut bytes = ktx2([37, 2, 2, 0, 0, 1, 1, 0], &[&[0; 16]]);
+        bytes.truncate(bytes.len() - 1);
+        assert_eq!(TextureContainer::from_bytes(&bytes), Err("File is truncated!".to_owned()));
+        assert_eq!(TextureContainer::from_bytes(&KTX2_IDENTIFIER), Err("File is truncated!".to_owned()));
+    }
+
+    #[test]
+    fn unknown_container() {
+        assert!(TextureContainer::from_bytes(b"not a texture").is_err());
+    }
+}
@@ -0,0 +1,366 @@
+mod dds;
+mod ktx;
+
+use crate::render::texture::{CubeFace, Texture2D, Texture2DArr, TextureBouncer, TextureCube, UnboundTexture};
+use crate::{unwrap_option_or_ret, unwrap_result_or_ret};
+use crate::{Half, PackedDepth24Stencil8, PackedR11FG11FB10F, PackedRGB10A2};
+use gl::types::*;
+use std::convert::{TryFrom, TryInto};
+use std::path::Path;
+
+/// Converts little endian bytes to elements of the type opengl expects for typ and evaluates body with them as data
+macro_rules! with_typed_data {
+    ($typ:expr, $bytes:expr, |$data:ident| $body:expr) => {
+        match $typ {
+            gl::UNSIGNED_BYTE => { let $data = $bytes.to_vec(); $body }
+            gl::BYTE => { let $data = from_le_bytes($bytes, i8::from_le_bytes); $body }
+            gl::UNSIGNED_SHORT => { let $data = from_le_bytes($bytes, u16::from_le_bytes); $body }
+            gl::SHORT => { let $data = from_le_bytes($bytes, i16::from_le_bytes); $body }
+            gl::UNSIGNED_INT => { let $data = from_le_bytes($bytes, u32::from_le_bytes); $body }
+            gl::INT => { let $data = from_le_bytes($bytes, i32::from_le_bytes); $body }
+            gl::FLOAT => { let $data = from_le_bytes($bytes, f32::from_le_bytes); $body }
+            gl::HALF_FLOAT => { let $data = from_le_bytes($bytes, |b| Half(u16::from_le_bytes(b))); $body }
+            gl::UNSIGNED_INT_2_10_10_10_REV => { let $data = from_le_bytes($bytes, |b| PackedRGB10A2(u32::from_le_bytes(b))); $body }
+            gl::UNSIGNED_INT_10F_11F_11F_REV => { let $data = from_le_bytes($bytes, |b| PackedR11FG11FB10F(u32::from_le_bytes(b))); $body }
+            gl::UNSIGNED_INT_24_8 => { let $data = from_le_bytes($bytes, |b| PackedDepth24Stencil8(u32::from_le_bytes(b))); $body }
+            typ => Err(format!("Unsupported data type: {:#x}!", typ)),
+        }
+    };
+}
+
+fn from_le_bytes<T, const S: usize>(bytes: &[u8], convert: impl Fn([u8; S]) -> T) -> Vec<T> {
+    bytes
+        .chunks_exact(S)
+        .map(|chunk| convert(chunk.try_into().expect("Chunks are exactly S bytes long!")))
+        .collect()
+}
+
+/// Every dimension halves with each mip level but never goes below 1
+fn get_size_of_mip_level(size: [usize; 3], level: usize) -> [usize; 3] {
+    let mut size = size;
+    for s in size.iter_mut() {
+        *s = u32::try_from(level).ok().and_then(|l| s.checked_shr(l)).unwrap_or(0).max(1);
+    }
+    size
+}
+
+/// Reads the fixed size fields of container headers, every read is bounds checked so truncated files are reported instead of panicking
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        ByteReader {
+            bytes,
+            pos: 0,
+            big_endian: false,
+        }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = unwrap_option_or_ret!(self.pos.checked_add(len), Err("File is truncated!".to_owned()));
+        let r = unwrap_option_or_ret!(self.bytes.get(self.pos..end), Err("File is truncated!".to_owned()));
+        self.pos = end;
+        Ok(r)
+    }
+
+    #[inline(always)]
+    fn skip(&mut self, len: usize) -> Result<(), String> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    /// Skips to the next multiple of alignment from the start of the file
+    #[inline(always)]
+    fn align(&mut self, alignment: usize) -> Result<(), String> {
+        self.skip((alignment - self.pos % alignment) % alignment)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, String> {
+        let b: [u8; 4] = self.read_bytes(4)?.try_into().expect("Read exactly 4 bytes!");
+        Ok(if self.big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    fn read_u64(&mut self) -> Result<u64, String> {
+        let b: [u8; 8] = self.read_bytes(8)?.try_into().expect("Read exactly 8 bytes!");
+        Ok(if self.big_endian { u64::from_be_bytes(b) } else { u64::from_le_bytes(b) })
+    }
+
+    #[inline(always)]
+    fn read_usize(&mut self) -> Result<usize, String> {
+        Ok(unwrap_result_or_ret!(self.read_u32()?.try_into(), Err("Value too big for this platform!".to_owned())))
+    }
+}
+
+/// A texture read from a container file ( KTX1, KTX2 or DDS ) with every image of every mip level, ready to be turned into a texture
+/// NOTE: Whatever the file stored, the data is little endian and tightly packed ( rows have no padding )
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureContainer {
+    internal_format: GLenum,
+    /// The type and format of the data, None for compressed formats
+    data_format: Option<(GLenum, GLenum)>,
+    /// Width, height and depth of mip level 0
+    size: [usize; 3],
+    /// The number of layers of array textures, None for textures that are not arrays
+    layers: Option<usize>,
+    faces: usize,
+    /// Every level holds one image for each layer and face, faces change fastest, just like opengl lays out cube map arrays
+    levels: Vec<Vec<u8>>,
+    /// Set for files that ask for the mip levels to be generated when loading them instead of storing them
+    generate_mipmaps: bool,
+}
+
+impl TextureContainer {
+    fn new(
+        internal_format: GLenum,
+        data_format: Option<(GLenum, GLenum)>,
+        size: [usize; 3],
+        layers: Option<usize>,
+        faces: usize,
+        levels: Vec<Vec<u8>>,
+    ) -> Result<Self, String> {
+        match data_format {
+            None if !crate::is_compressed_internal_format(internal_format) => {
+                return Err(format!("Unsupported compressed internal format: {:#x}!", internal_format));
+            }
+            Some((typ, format)) if !crate::is_format_compatible_with_internal_format(internal_format, typ, format) => {
+                return Err(format!("Data of type {:#x} and format {:#x} can't be stored as internal format {:#x}!", typ, format, internal_format));
+            }
+            _ => {}
+        }
+        if size.contains(&0) || layers == Some(0) {
+            return Err(format!("Texture of size {:?} with {:?} layers is empty!", size, layers));
+        }
+        if faces != 1 && faces != 6 {
+            return Err(format!("Textures have either 1 face or 6 faces ( cube maps ), not {}!", faces));
+        }
+        if faces == 6 && (size[0] != size[1] || size[2] != 1) {
+            return Err(format!("Cube map faces must be square, got {} pixels * {} pixels * {} pixels!", size[0], size[1], size[2]));
+        }
+        let r = TextureContainer {
+            internal_format,
+            data_format,
+            size,
+            layers,
+            faces,
+            levels,
+            generate_mipmaps: false,
+        };
+        let max_levels = (usize::BITS - size.iter().max().copied().unwrap_or(1).leading_zeros()) as usize;
+        if r.levels.is_empty() || r.levels.len() > max_levels {
+            return Err(format!("A texture of size {:?} can have between 1 and {} mip levels, not {}!", size, max_levels, r.levels.len()));
+        }
+        for (level, data) in r.levels.iter().enumerate() {
+            let expected_len = unwrap_option_or_ret!(
+                r.get_image_len(level).and_then(|len| len.checked_mul(r.get_num_of_images())),
+                Err(format!("Mip level {} is too big!", level))
+            );
+            if data.len() != expected_len {
+                return Err(format!("Mip level {} is {} bytes but {} bytes were expected!", level, data.len(), expected_len));
+            }
+        }
+        Ok(r)
+    }
+
+    /// Detects the kind of container from the start of the data
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.starts_with(&ktx::KTX1_IDENTIFIER) {
+            ktx::parse_ktx1(bytes)
+        } else if bytes.starts_with(&ktx::KTX2_IDENTIFIER) {
+            ktx::parse_ktx2(bytes)
+        } else if bytes.starts_with(dds::DDS_MAGIC) {
+            dds::parse_dds(bytes)
+        } else {
+            Err("Unknown texture container, only KTX1, KTX2 and DDS files are supported!".to_owned())
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let bytes = unwrap_result_or_ret!(std::fs::read(path.as_ref()), Err(format!("Couldn't read {}!", path.as_ref().display())));
+        Self::from_bytes(&bytes)
+    }
+
+    #[inline(always)]
+    pub fn get_internal_format(&self) -> GLenum {
+        self.internal_format
+    }
+
+    /// The type and format of the data, None for compressed formats
+    #[inline(always)]
+    pub fn get_data_format(&self) -> Option<(GLenum, GLenum)> {
+        self.data_format
+    }
+
+    /// Width, height and depth of mip level 0
+    #[inline(always)]
+    pub fn get_size(&self) -> [usize; 3] {
+        self.size
+    }
+
+    /// The number of layers of array textures, None for textures that are not arrays
+    #[inline(always)]
+    pub fn get_layers(&self) -> Option<usize> {
+        self.layers
+    }
+
+    /// 6 for cube maps, 1 otherwise
+    #[inline(always)]
+    pub fn get_faces(&self) -> usize {
+        self.faces
+    }
+
+    /// The number of mip levels stored in the file
+    #[inline(always)]
+    pub fn get_num_of_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// True if the file asks for the mip levels to be generated when loading it
+    #[inline(always)]
+    pub fn should_generate_mipmaps(&self) -> bool {
+        self.generate_mipmaps
+    }
+
+    #[inline(always)]
+    pub fn get_size_of_level(&self, level: usize) -> [usize; 3] {
+        get_size_of_mip_level(self.size, level)
+    }
+
+    /// All the images of a mip level, one for each layer and face with faces changing fastest
+    #[inline(always)]
+    pub fn get_level_data(&self, level: usize) -> Option<&[u8]> {
+        self.levels.get(level).map(|data| data.as_slice())
+    }
+
+    pub fn get_image(&self, level: usize, layer: usize, face: usize) -> Option<&[u8]> {
+        if layer >= self.layers.unwrap_or(1) || face >= self.faces {
+            return None;
+        }
+        let len = self.get_image_len(level)?;
+        let start = (layer * self.faces + face) * len;
+        self.get_level_data(level)?.get(start..start + len)
+    }
+
+    #[inline(always)]
+    fn get_num_of_images(&self) -> usize {
+        self.layers.unwrap_or(1) * self.faces
+    }
+
+    /// The size in bytes of one image ( one layer of one face ) of a mip level
+    fn get_image_len(&self, level: usize) -> Option<usize> {
+        let size = self.get_size_of_level(level);
+        match self.data_format {
+            None => crate::get_compressed_image_size(self.internal_format, &size),
+            Some((typ, format)) => {
+                let bpp = usize::from(crate::get_elements_per_pixel(typ, format)?) * usize::from(crate::get_size_of_gl_type(typ)?);
+                size.iter().try_fold(bpp, |acc, s| acc.checked_mul(*s))
+            }
+        }
+    }
+
+    /// The number of mip levels the texture needs, generated mip levels can't be compressed
+    fn get_num_of_levels_to_allocate(&self) -> usize {
+        if self.generate_mipmaps && self.data_format.is_some() {
+            (usize::BITS - self.size[0].max(self.size[1]).leading_zeros()) as usize
+        } else {
+            self.levels.len()
+        }
+    }
+
+    fn validate_2d(&self) -> Result<(), String> {
+        if self.size[2] != 1 {
+            return Err("Container holds a 3D texture!".to_owned());
+        }
+        if self.faces != 1 {
+            return Err("Container holds a cube map!".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Creates a texture with immutable storage and every mip level in the file, with mipmapped filtering if there is more than one mip level
+    pub fn create_texture_2d<const BI: usize>(&self, bn: &mut TextureBouncer<BI>) -> Result<UnboundTexture<2, { gl::TEXTURE_2D }>, String> {
+        self.validate_2d()?;
+        if self.layers.is_some() {
+            return Err("Container holds an array texture, use create_texture_2d_array instead!".to_owned());
+        }
+        let levels = self.get_num_of_levels_to_allocate();
+        let mut r = Texture2D::with_immutable_storage(bn, levels, self.internal_format, [self.size[0], self.size[1]])?;
+        {
+            let mut r = r.bind_mut(bn);
+            for (level, data) in self.levels.iter().enumerate() {
+                let [w, h, _] = self.get_size_of_level(level);
+                match self.data_format {
+                    None => r.update_compressed_region_of_level(level, [0, 0], [w, h], data)?,
+                    Some((typ, format)) => with_typed_data!(typ, data, |data| r.update_region_of_level_with_format(level, [0, 0], [w, h], &data, format))?,
+                }
+            }
+            if levels > self.levels.len() {
+                r.generate_mipmaps_of_bound_tex()?;
+            }
+            if levels > 1 {
+                r.set_min_filter_of_bound_tex(gl::LINEAR_MIPMAP_LINEAR)?;
+            }
+        }
+        Ok(r)
+    }
+
+    /// Same as create_texture_2d, textures that are not arrays become an array texture with one layer
+    pub fn create_texture_2d_array<const BI: usize>(&self, bn: &mut TextureBouncer<BI>) -> Result<UnboundTexture<3, { gl::TEXTURE_2D_ARRAY }>, String> {
+        self.validate_2d()?;
+        let layers = self.layers.unwrap_or(1);
+        let levels = self.get_num_of_levels_to_allocate();
+        let mut r = Texture2DArr::with_immutable_storage(bn, levels, self.internal_format, [self.size[0], self.size[1], layers])?;
+        {
+            let mut r = r.bind_mut(bn);
+            for (level, data) in self.levels.iter().enumerate() {
+                let [w, h, _] = self.get_size_of_level(level);
+                // NOTE: The layers of a level are stored one after the other, which is exactly how opengl expects a whole level of an array texture
+                match self.data_format {
+                    None => r.update_compressed_region_of_level(level, [0, 0, 0], [w, h, layers], data)?,
+                    Some((typ, format)) => with_typed_data!(typ, data, |data| r.update_region_of_level_with_format(level, [0, 0, 0], [w, h, layers], &data, format))?,
+                }
+            }
+            if levels > self.levels.len() {
+                r.generate_mipmaps_of_bound_tex()?;
+            }
+            if levels > 1 {
+                r.set_min_filter_of_bound_tex(gl::LINEAR_MIPMAP_LINEAR)?;
+            }
+        }
+        Ok(r)
+    }
+
+    /// Same as create_texture_2d but for cube maps
+    pub fn create_texture_cube<const BI: usize>(&self, bn: &mut TextureBouncer<BI>) -> Result<UnboundTexture<2, { gl::TEXTURE_CUBE_MAP }>, String> {
+        if self.faces != 6 {
+            return Err("Container does not hold a cube map!".to_owned());
+        }
+        if self.layers.is_some() {
+            return Err("Container holds a cube map array!".to_owned());
+        }
+        let levels = self.get_num_of_levels_to_allocate();
+        let mut r = TextureCube::with_immutable_storage(bn, levels, self.internal_format, [self.size[0], self.size[1]])?;
+        {
+            let mut r = r.bind_mut(bn);
+            for level in 0..self.levels.len() {
+                let [w, h, _] = self.get_size_of_level(level);
+                for (i, face) in CubeFace::ALL.iter().enumerate() {
+                    let data = unwrap_option_or_ret!(self.get_image(level, 0, i), Err(format!("Face {:?} of mip level {} is missing!", face, level)));
+                    match self.data_format {
+                        None => r.update_compressed_cube_face_region(*face, level, [0, 0], [w, h], data)?,
+                        Some((typ, format)) => with_typed_data!(typ, data, |data| r.update_cube_face_region_with_format(*face, level, [0, 0], [w, h], &data, format))?,
+                    }
+                }
+            }
+            if levels > self.levels.len() {
+                r.generate_mipmaps_of_bound_tex()?;
+            }
+            if levels > 1 {
+                r.set_min_filter_of_bound_tex(gl::LINEAR_MIPMAP_LINEAR)?;
+            }
+        }
+        Ok(r)
+    }
+}